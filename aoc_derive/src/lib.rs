@@ -0,0 +1,267 @@
+//! `#[derive(AocParse)]` generates a `FromStr` implementation from a line
+//! format such as `#[aoc(format = "{min}-{max} {letter}: {password}")]`.
+//!
+//! - `{name}` parses the named field (or `{0}` for tuple fields) with its own
+//!   `FromStr`, taking the text up to the literal that follows it.
+//! - `{name:sep}` parses a `Vec` field from `sep`-separated values.
+//! - `{{` and `}}` match literal braces.
+//!
+//! On enums, every variant is keyed by the first word of its format. Without
+//! an explicit format a variant reads as its lowercased name followed by its
+//! fields separated by spaces, e.g. `forward 5` for `Forward(i32)`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, LitStr, Result};
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field { name: String, sep: Option<String> },
+}
+
+fn parse_format(format: &LitStr) -> Result<Vec<Segment>> {
+    let text = format.value();
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => return Err(Error::new(format.span(), "unclosed `{` in format")),
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                } else if matches!(segments.last(), Some(Segment::Field { .. })) {
+                    return Err(Error::new(
+                        format.span(),
+                        "fields must be separated by literal text",
+                    ));
+                }
+                let (name, sep) = match field.split_once(':') {
+                    Some((name, sep)) if !sep.is_empty() => (name, Some(sep.to_string())),
+                    Some(_) => return Err(Error::new(format.span(), "empty separator in format")),
+                    None => (field.as_str(), None),
+                };
+                segments.push(Segment::Field {
+                    name: name.trim().to_string(),
+                    sep,
+                });
+            }
+            '}' => return Err(Error::new(format.span(), "unmatched `}` in format")),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn format_attr(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    let mut format = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(format)
+}
+
+/// The names used for the fields in a format, in declaration order.
+fn field_names(fields: &Fields) -> Vec<String> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        })
+        .collect()
+}
+
+fn default_format(name: &str, fields: &Fields) -> String {
+    let mut format = name.to_lowercase();
+    for field in field_names(fields) {
+        format.push_str(&format!(" {{{}}}", field));
+    }
+    format
+}
+
+/// Generates a block that parses `cursor` according to `segments` and
+/// evaluates to `constructor` built from the parsed fields.
+fn parse_body(
+    segments: &[Segment],
+    fields: &Fields,
+    constructor: TokenStream2,
+    span: Span,
+) -> Result<TokenStream2> {
+    let names = field_names(fields);
+    let mut steps = Vec::new();
+    let mut seen = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! { cursor.literal(#literal)?; }),
+            Segment::Field { name, sep } => {
+                let index = names.iter().position(|n| n == name).ok_or_else(|| {
+                    Error::new(span, format!("unknown field `{}` in format", name))
+                })?;
+                if seen.contains(&index) {
+                    return Err(Error::new(
+                        span,
+                        format!("field `{}` appears twice in format", name),
+                    ));
+                }
+                seen.push(index);
+                let var = format_ident!("field_{}", index);
+                let ty = &fields.iter().nth(index).unwrap().ty;
+                let until = match segments.get(i + 1) {
+                    Some(Segment::Literal(literal)) => quote! { Some(#literal) },
+                    _ => quote! { None },
+                };
+                steps.push(match sep {
+                    Some(sep) => quote! { let #var: #ty = cursor.repeated(#name, #sep, #until)?; },
+                    None => quote! { let #var: #ty = cursor.field(#name, #until)?; },
+                });
+            }
+        }
+    }
+    if let Some(missing) = (0..names.len()).find(|i| !seen.contains(i)) {
+        return Err(Error::new(
+            span,
+            format!("field `{}` is missing from format", names[missing]),
+        ));
+    }
+    let vars = (0..names.len()).map(|i| format_ident!("field_{}", i));
+    let value = match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| f.ident.as_ref().unwrap());
+            quote! { #constructor { #(#idents: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { #constructor(#(#vars),*) },
+        Fields::Unit => quote! { #constructor },
+    };
+    Ok(quote! {
+        {
+            #(#steps)*
+            cursor.finish()?;
+            Ok(#value)
+        }
+    })
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let span = ident.span();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = format_attr(&input.attrs)?.ok_or_else(|| {
+                Error::new(span, "structs need a `#[aoc(format = \"...\")]` attribute")
+            })?;
+            parse_body(
+                &parse_format(&format)?,
+                &data.fields,
+                quote! { #ident },
+                format.span(),
+            )?
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            let mut keywords = Vec::new();
+            for variant in &data.variants {
+                let var_ident = &variant.ident;
+                let (segments, span) = match format_attr(&variant.attrs)? {
+                    Some(format) => (parse_format(&format)?, format.span()),
+                    None => {
+                        let format = default_format(&var_ident.to_string(), &variant.fields);
+                        let format = LitStr::new(&format, var_ident.span());
+                        (parse_format(&format)?, var_ident.span())
+                    }
+                };
+                let keyword = match segments.first() {
+                    Some(Segment::Literal(literal)) => literal
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    _ => String::new(),
+                };
+                if keyword.is_empty() {
+                    return Err(Error::new(
+                        span,
+                        "enum variant formats must start with a word",
+                    ));
+                }
+                if keywords.contains(&keyword) {
+                    return Err(Error::new(
+                        span,
+                        format!("duplicate leading word `{}`", keyword),
+                    ));
+                }
+                let body = parse_body(
+                    &segments,
+                    &variant.fields,
+                    quote! { #ident::#var_ident },
+                    span,
+                )?;
+                arms.push(quote! { #keyword => #body });
+                keywords.push(keyword);
+            }
+            let expected = keywords
+                .iter()
+                .map(|k| format!("`{}`", k))
+                .collect::<Vec<_>>()
+                .join(", ");
+            quote! {
+                match cursor.leading_word() {
+                    #(#arms)*
+                    word => Err(cursor.error(
+                        0,
+                        format!("unknown `{}`, expected one of {}", word, #expected),
+                    )),
+                }
+            }
+        }
+        Data::Union(_) => return Err(Error::new(span, "AocParse can't be derived for unions")),
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = ::aoc::format::FormatError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #[allow(unused_mut)]
+                let mut cursor = ::aoc::format::Cursor::new(s);
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,41 @@
+// Times every part that has alternative implementations next to them, on the
+// day's input. With `--features count-allocations`, also prints what each of
+// them allocates.
+
+use aoc::allocations;
+use aoc::registry::{Solver, DAYS};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn print_allocations(group: &str, name: &str, solve: Solver, input: &str) {
+    if let (_, Some(stats)) = allocations::measure(|| solve(input)) {
+        println!("{}/{}: {}", group, name, stats);
+    }
+}
+
+fn bench_alternatives(c: &mut Criterion) {
+    for day in DAYS {
+        for (i, part) in day.parts.iter().enumerate() {
+            if part.alternatives.is_empty() {
+                continue;
+            }
+            let name = format!("{}/part_{}", day.name(), i + 1);
+            print_allocations(&name, &format!("part_{}", i + 1), part.solve, day.input);
+            for alternative in part.alternatives {
+                print_allocations(&name, alternative.name, alternative.solve, day.input);
+            }
+            let mut group = c.benchmark_group(name);
+            group.bench_function(format!("part_{}", i + 1), |b| {
+                b.iter(|| (part.solve)(black_box(day.input)))
+            });
+            for alternative in part.alternatives {
+                group.bench_function(alternative.name, |b| {
+                    b.iter(|| (alternative.solve)(black_box(day.input)))
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, bench_alternatives);
+criterion_main!(benches);
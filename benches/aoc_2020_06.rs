@@ -0,0 +1,55 @@
+use aoc::aoc_2020_06::{
+    count_group_all, count_group_all_bits, count_group_any, count_group_any_bits, INPUT,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn groups() -> Vec<&'static str> {
+    INPUT.trim().split("\n\n").collect()
+}
+
+fn bench_any(c: &mut Criterion) {
+    let groups = groups();
+    let mut group = c.benchmark_group("aoc_2020_06/count_group_any");
+    group.bench_function("hashed", |b| {
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| count_group_any(black_box(g)))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("bits", |b| {
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| count_group_any_bits(black_box(g)))
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_all(c: &mut Criterion) {
+    let groups = groups();
+    let mut group = c.benchmark_group("aoc_2020_06/count_group_all");
+    group.bench_function("hashed", |b| {
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| count_group_all(black_box(g)))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("bits", |b| {
+        b.iter(|| {
+            groups
+                .iter()
+                .map(|g| count_group_all_bits(black_box(g)))
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_any, bench_all);
+criterion_main!(benches);
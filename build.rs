@@ -0,0 +1,118 @@
+// Turns the extra examples in `src/aoc_YYYY_DD/examples/` into a table and
+// tests for each day, included by `aoc_day!`.
+//
+// Every `<name>.txt` needs a `<name>.answers` sidecar with the expected
+// answer for each part it applies to, see `src/answers.rs`.
+
+#[path = "src/answers.rs"]
+mod answers;
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct Example {
+    name: String,
+    input: PathBuf,
+    answers: [Option<String>; 2],
+}
+
+fn parse_answers(path: &Path) -> [Option<String>; 2] {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+    let answers = answers::parse(&text).unwrap_or_else(|e| panic!("{}:{}", path.display(), e));
+    if answers.iter().all(Option::is_none) {
+        panic!("{}: no answers", path.display());
+    }
+    answers
+}
+
+fn read_examples(dir: &Path) -> Vec<Example> {
+    let mut examples = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return examples;
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid || name == "example" {
+            panic!(
+                "{}: example names must be lowercase identifiers other than `example`",
+                path.display()
+            );
+        }
+        let answers = parse_answers(&path.with_extension("answers"));
+        examples.push(Example {
+            name,
+            input: path.canonicalize().unwrap(),
+            answers,
+        });
+    }
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    examples
+}
+
+fn generate(examples: &[Example]) -> String {
+    let mut out = String::new();
+    out.push_str("pub static EXAMPLES: &[crate::registry::Example] = &[\n");
+    for example in examples {
+        let answers = example
+            .answers
+            .iter()
+            .map(|a| match a {
+                Some(a) => format!("Some({:?})", a),
+                None => "None".to_string(),
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "    crate::registry::Example {{ name: {:?}, input: include_str!({:?}), answers: [{}] }},",
+            example.name,
+            example.input,
+            answers.join(", ")
+        )
+        .unwrap();
+    }
+    out.push_str("];\n\n#[cfg(test)]\nmod examples {\n");
+    for (i, example) in examples.iter().enumerate() {
+        writeln!(
+            out,
+            "    #[test]\n    fn {}() {{\n        super::DAY.check_example(&super::EXAMPLES[{}]);\n    }}",
+            example.name, i
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    for entry in fs::read_dir("src").unwrap() {
+        let dir = entry.unwrap().path();
+        let Some(name) = dir.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // `aoc_2020_01` is written to `examples_2020_1.rs`, matching the
+        // literals given to `aoc_day!`.
+        let Some((year, day)) = name
+            .strip_prefix("aoc_")
+            .and_then(|date| date.split_once('_'))
+            .and_then(|(year, day)| Some((year.parse::<u16>().ok()?, day.parse::<u8>().ok()?)))
+        else {
+            continue;
+        };
+        let examples = read_examples(&dir.join("examples"));
+        let file = out_dir.join(format!("examples_{}_{}.rs", year, day));
+        fs::write(file, generate(&examples)).unwrap();
+    }
+}
@@ -0,0 +1,15 @@
+// Counts sonar depth increases from stdin, e.g.
+// `cat depths.txt | cargo run --example depth_increases -- 3`
+
+use aoc::aoc_2021_01::count_increases_from_reader;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let size = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse::<usize>().expect("window size must be a number"))
+        .unwrap_or(1);
+    let increases = count_increases_from_reader(io::stdin().lock(), size)?;
+    println!("{}", increases);
+    Ok(())
+}
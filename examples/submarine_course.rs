@@ -0,0 +1,44 @@
+// Draws the part 1 and part 2 courses for a submarine program, e.g.
+// `cargo run --example submarine_course -- src/aoc_2021_02/input.txt > course.svg`
+// Pass `--csv` to print the part 2 trajectory as CSV instead.
+
+use aoc::aoc_2021_02::{to_csv, to_svg, Aimed, Direct, Program, Series, Submarine, SvgOptions};
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let csv = args.iter().any(|a| a == "--csv");
+    let path = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .ok_or("usage: submarine_course [--csv] <program>")?;
+    let program: Program = std::fs::read_to_string(path)?.parse()?;
+
+    let mut direct = Submarine::new(Direct);
+    direct.run(program.commands())?;
+    let mut aimed = Submarine::new(Aimed);
+    aimed.run(program.commands())?;
+
+    if csv {
+        print!("{}", to_csv(aimed.trajectory()));
+    } else {
+        let series = [
+            Series {
+                name: "direct",
+                color: "steelblue",
+                trajectory: direct.trajectory(),
+            },
+            Series {
+                name: "aimed",
+                color: "darkorange",
+                trajectory: aimed.trajectory(),
+            },
+        ];
+        let options = SvgOptions {
+            marker_every: 100,
+            ..SvgOptions::default()
+        };
+        print!("{}", to_svg(&series, &options));
+    }
+    Ok(())
+}
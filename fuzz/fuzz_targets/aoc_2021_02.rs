@@ -0,0 +1,18 @@
+#![no_main]
+
+// Both parts on arbitrary programs. Repeat blocks can make a tiny program run
+// for a very long time, so programs that expand to many commands are only
+// parsed.
+
+use aoc::aoc_2021_02::{parse, part_1, part_2};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let Ok(program) = parse(input) else {
+        return;
+    };
+    if program.command_count().is_some_and(|n| n <= 100_000) {
+        let _ = part_1(&program);
+        let _ = part_2(&program);
+    }
+});
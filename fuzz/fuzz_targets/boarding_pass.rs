@@ -0,0 +1,12 @@
+#![no_main]
+
+// Both ways of decoding a pass must accept the same passes and agree on the
+// seat ID.
+
+use aoc::aoc_2020_05::{seat_from_str, seat_id_bits};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let seat = seat_from_str(input).ok().map(|seat| seat.id);
+    assert_eq!(seat, seat_id_bits(input).ok());
+});
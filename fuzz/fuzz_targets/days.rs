@@ -0,0 +1,29 @@
+#![no_main]
+
+// Both parts and their alternatives of every day on arbitrary input: they may
+// return an error, but must not panic. The first byte picks the day, in the
+// order of `registry::DAYS`, and the rest is the input. aoc_2021_02 has a
+// target of its own, as its programs can run for a very long time.
+
+use aoc::registry::DAYS;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&index, input)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return;
+    };
+    let days = DAYS
+        .iter()
+        .filter(|day| (day.year, day.day) != (2021, 2))
+        .collect::<Vec<_>>();
+    let day = days[usize::from(index) % days.len()];
+    for part in &day.parts {
+        let _ = (part.solve)(input);
+        for alternative in part.alternatives {
+            let _ = (alternative.solve)(input);
+        }
+    }
+});
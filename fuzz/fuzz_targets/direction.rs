@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc::aoc_2021_02::Direction;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Direction>();
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc::aoc_2020_04::parse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = parse(input);
+});
@@ -0,0 +1,47 @@
+#!/bin/sh
+# Seeds fuzz/corpus/<target> from the days' example.txt, input.txt and named
+# examples. Whole files seed the day targets, after the byte that picks the
+# day for `days`; single lines (or passports, for `passport_fields`) seed the
+# parser targets.
+set -eu
+cd "$(dirname "$0")"
+
+seed_files() {
+    target=$1
+    day=$2
+    index=${3-}
+    mkdir -p "corpus/$target"
+    for file in "../src/$day/example.txt" "../src/$day/input.txt" "../src/$day"/examples/*.txt; do
+        [ -f "$file" ] || continue
+        seed="corpus/$target/$(echo "$file" | tr / _ | sed 's/^\.\._src_//')"
+        if [ -n "$index" ]; then
+            printf "\\$(printf %03o "$index")" >"$seed"
+            cat "$file" >>"$seed"
+        else
+            cp "$file" "$seed"
+        fi
+    done
+}
+
+seed_lines() {
+    target=$1
+    day=$2
+    mkdir -p "corpus/$target"
+    sort -u "../src/$day/example.txt" "../src/$day/input.txt" | head -n 200 |
+        awk -v dir="corpus/$target" '{ file = dir "/line_" NR; printf "%s", $0 > file; close(file) }'
+}
+
+# The day modules sort in the same order as `registry::DAYS`.
+index=0
+for dir in ../src/aoc_*/; do
+    day=$(basename "$dir")
+    [ "$day" != aoc_2021_02 ] || continue
+    seed_files days "$day" "$index"
+    index=$((index + 1))
+done
+seed_files aoc_2021_02 aoc_2021_02
+seed_files passport_fields aoc_2020_04
+seed_files program aoc_2021_02
+seed_lines password_record aoc_2020_02
+seed_lines boarding_pass aoc_2020_05
+seed_lines direction aoc_2021_02
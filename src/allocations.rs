@@ -0,0 +1,178 @@
+// Counts allocations and peak memory of a closure, to find parts that could
+// do without them. Opt in with the `count-allocations` feature, which installs
+// `CountingAllocator` as the global allocator; without it `measure` returns
+// `None`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+/// What a closure allocated on its own thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: u64,
+    /// Bytes allocated in total, including reallocations.
+    pub bytes: u64,
+    /// The most bytes that were live at once, not counting what was allocated
+    /// before the closure ran.
+    pub peak: u64,
+}
+
+fn kib(bytes: u64) -> String {
+    format!("{:.1} KiB", bytes as f64 / 1024.0)
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {}, peak {}",
+            self.allocations,
+            kib(self.bytes),
+            kib(self.peak)
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    /// Can go below zero when memory from before `measure` is freed.
+    live: i64,
+    peak: i64,
+}
+
+impl Counters {
+    const ZERO: Counters = Counters {
+        allocations: 0,
+        bytes: 0,
+        live: 0,
+        peak: 0,
+    };
+}
+
+thread_local! {
+    // Constant and without a destructor, so the allocator can use it at any
+    // point of a thread's life without allocating itself.
+    static COUNTERS: Cell<Counters> = const { Cell::new(Counters::ZERO) };
+}
+
+fn record(allocated: Option<usize>, freed: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        if let Some(size) = allocated {
+            c.allocations += 1;
+            c.bytes += size as u64;
+            c.live += size as i64;
+        }
+        c.live -= freed as i64;
+        c.peak = c.peak.max(c.live);
+        counters.set(c);
+    });
+}
+
+/// The system allocator, counting what each thread allocates.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(Some(layout.size()), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(Some(layout.size()), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(None, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(Some(new_size), layout.size());
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f` and returns what it allocated on this thread, if allocations are
+/// being counted.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Stats>) {
+    if !cfg!(feature = "count-allocations") {
+        return (f(), None);
+    }
+    let before = COUNTERS.with(|counters| counters.replace(Counters::ZERO));
+    let result = f();
+    // Count this for an enclosing `measure` too.
+    let counted = COUNTERS.with(|counters| {
+        counters.replace(Counters {
+            allocations: before.allocations + counters.get().allocations,
+            bytes: before.bytes + counters.get().bytes,
+            live: before.live + counters.get().live,
+            peak: before.peak.max(before.live + counters.get().peak),
+        })
+    });
+    let stats = Stats {
+        allocations: counted.allocations,
+        bytes: counted.bytes,
+        peak: counted.peak.max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let stats = Stats {
+            allocations: 3,
+            bytes: 3072,
+            peak: 1536,
+        };
+        assert_eq!(stats.to_string(), "3 allocations, 3.0 KiB, peak 1.5 KiB");
+    }
+
+    #[cfg(not(feature = "count-allocations"))]
+    #[test]
+    fn test_measure_without_counting() {
+        assert_eq!(measure(|| vec![0u8; 100].len()), (100, None));
+    }
+
+    #[cfg(feature = "count-allocations")]
+    #[test]
+    fn test_measure() {
+        let (_, stats) = measure(|| {
+            let small = vec![0u8; 100];
+            drop(small);
+            let mut big = Vec::<u8>::with_capacity(1000);
+            big.push(1);
+            big
+        });
+        assert_eq!(
+            stats,
+            Some(Stats {
+                allocations: 2,
+                bytes: 1100,
+                peak: 1000,
+            })
+        );
+        assert_eq!(measure(|| 1 + 1).1.unwrap().allocations, 0);
+    }
+}
@@ -0,0 +1,49 @@
+// The `.answers` sidecar files next to example and personal inputs, with the
+// expected answer for each part it's known for:
+//
+//     part_1: 357
+//     part_2: 10
+//
+// Also used by `build.rs`, so this only depends on std.
+
+/// The expected answers for part 1 and 2, or a `line: message` error.
+pub fn parse(text: &str) -> Result<[Option<String>; 2], String> {
+    let mut answers = [None, None];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (part, answer) = match line.split_once(':') {
+            Some(("part_1", answer)) => (0, answer),
+            Some(("part_2", answer)) => (1, answer),
+            Some((key, _)) => return Err(format!("{}: unknown part `{}`", i + 1, key)),
+            None => return Err(format!("{}: expected `part_N: answer`", i + 1)),
+        };
+        answers[part] = Some(answer.trim().to_string());
+    }
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("part_1: 12\r\n\npart_2:10\n"),
+            Ok([Some("12".to_string()), Some("10".to_string())])
+        );
+        assert_eq!(parse("part_2: x y\n"), Ok([None, Some("x y".to_string())]));
+        assert_eq!(parse(""), Ok([None, None]));
+        assert_eq!(
+            parse("part_3: 1"),
+            Err("1: unknown part `part_3`".to_string())
+        );
+        assert_eq!(
+            parse("\n42"),
+            Err("2: expected `part_N: answer`".to_string())
+        );
+    }
+}
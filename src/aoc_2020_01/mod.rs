@@ -1,66 +1,220 @@
 extern crate itertools;
 
+use crate::input;
+use crate::overflow::Overflow;
+use crate::parse::ParseError;
+use crate::registry::aoc_day;
+use crate::rng::Rng;
 use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt;
 
-pub fn part_1(input: &str) -> i64 {
-    let numbers = input
-        .lines()
-        .filter_map(|l| l.trim().parse::<i64>().ok())
-        .collect::<Vec<_>>();
+mod oracle;
 
-    let pair = numbers
-        .iter()
-        .tuple_combinations()
-        .find(|&(a, b)| a + b == 2020)
-        .unwrap();
+/// Why a list of entries has no answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// No entries sum to 2020.
+    NotFound,
+    Overflow,
+}
 
-    pair.0 * pair.1
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "no entries sum to 2020"),
+            Error::Overflow => Overflow.fmt(f),
+        }
+    }
 }
 
-pub fn part_2(input: &str) -> i64 {
-    let numbers = input
-        .lines()
-        .filter_map(|l| l.trim().parse::<i64>().ok())
-        .collect::<Vec<_>>();
+impl std::error::Error for Error {}
 
-    let tuple = numbers
+impl From<Overflow> for Error {
+    fn from(_: Overflow) -> Self {
+        Error::Overflow
+    }
+}
+
+// Sums are compared as `i128` so hostile entries can't overflow them.
+
+fn pair_summing_to(numbers: &[i64], target: i64) -> Option<(i64, i64)> {
+    numbers
         .iter()
+        .copied()
         .tuple_combinations()
-        .find(|&(a, b, c)| a + b + c == 2020)
-        .unwrap();
+        .find(|&(a, b)| a as i128 + b as i128 == target as i128)
+}
 
-    tuple.0 * tuple.1 * tuple.2
+fn triple_summing_to(numbers: &[i64], target: i64) -> Option<(i64, i64, i64)> {
+    numbers
+        .iter()
+        .copied()
+        .tuple_combinations()
+        .find(|&(a, b, c)| a as i128 + b as i128 + c as i128 == target as i128)
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
+/// Same as `pair_summing_to`, but looks up each number's complement in the
+/// numbers seen so far instead of trying every pair.
+fn pair_summing_to_hashed(numbers: &[i64], target: i64) -> Option<(i64, i64)> {
+    let mut seen = HashSet::new();
+    numbers.iter().find_map(|&n| {
+        let found = target
+            .checked_sub(n)
+            .filter(|other| seen.contains(other))
+            .map(|other| (other, n));
+        seen.insert(n);
+        found
+    })
+}
 
-#[cfg(test)]
-mod part_1 {
-    use super::*;
+/// Same as `triple_summing_to`, but fixes the first number and looks for the
+/// other two with `pair_summing_to_hashed`.
+fn triple_summing_to_hashed(numbers: &[i64], target: i64) -> Option<(i64, i64, i64)> {
+    numbers.iter().enumerate().find_map(|(i, &a)| {
+        let rest = target.checked_sub(a)?;
+        pair_summing_to_hashed(&numbers[i + 1..], rest).map(|(b, c)| (a, b, c))
+    })
+}
 
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 514579);
-    }
+fn pair_product(pair: Option<(i64, i64)>) -> Result<i64, Error> {
+    let (a, b) = pair.ok_or(Error::NotFound)?;
+    Ok(a.checked_mul(b).ok_or(Overflow)?)
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 157059);
+fn triple_product(triple: Option<(i64, i64, i64)>) -> Result<i64, Error> {
+    let (a, b, c) = triple.ok_or(Error::NotFound)?;
+    Ok(a.checked_mul(b)
+        .and_then(|n| n.checked_mul(c))
+        .ok_or(Overflow)?)
+}
+
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    input::numbers(input)
+}
+
+pub fn part_1(numbers: &[i64]) -> Result<i64, Error> {
+    pair_product(pair_summing_to(numbers, 2020))
+}
+
+pub fn part_2(numbers: &[i64]) -> Result<i64, Error> {
+    triple_product(triple_summing_to(numbers, 2020))
+}
+
+pub fn part_1_hashed(numbers: &[i64]) -> Result<i64, Error> {
+    pair_product(pair_summing_to_hashed(numbers, 2020))
+}
+
+pub fn part_2_hashed(numbers: &[i64]) -> Result<i64, Error> {
+    triple_product(triple_summing_to_hashed(numbers, 2020))
+}
+
+/// `size` entries (at least 5) of which exactly one pair and one triple
+/// sum to 2020.
+///
+/// The planted pair and triple are drawn until they don't form any other
+/// sums among themselves. The other entries are all above 1010, so no two of
+/// them sum to 2020, and they avoid the complements of the planted entries.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let planted = loop {
+        let a = rng.between(1, 2019) as i64;
+        let b = rng.between(1, 1000) as i64;
+        let c = rng.between(1, 2019 - b as u64) as i64;
+        let planted = [a, 2020 - a, b, c, 2020 - b - c];
+        let pairs = planted
+            .iter()
+            .tuple_combinations()
+            .filter(|&(a, b)| a + b == 2020)
+            .count();
+        let triples = planted
+            .iter()
+            .tuple_combinations()
+            .filter(|&(a, b, c)| a + b + c == 2020)
+            .count();
+        if pairs == 1 && triples == 1 {
+            break planted;
+        }
+    };
+    let forbidden = planted
+        .iter()
+        .map(|a| 2020 - a)
+        .chain(
+            planted
+                .iter()
+                .tuple_combinations()
+                .map(|(a, b)| 2020 - a - b),
+        )
+        .collect::<HashSet<_>>();
+    let mut numbers = planted.to_vec();
+    while numbers.len() < size {
+        let n = rng.between(1011, 2019) as i64;
+        if !forbidden.contains(&n) {
+            numbers.push(n);
+        }
     }
+    rng.shuffle(&mut numbers);
+    numbers.iter().map(|n| format!("{}\n", n)).collect()
+}
+
+aoc_day! {
+    year: 2020,
+    day: 1,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 514579, answer: 157059, alternatives: [part_1_hashed] },
+    part_2: { example: 241861950, answer: 165080960, alternatives: [part_2_hashed] },
 }
 
 #[cfg(test)]
-mod part_2 {
+mod properties {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 241861950);
+    /// Whether `found` can be taken from `numbers` without reusing an entry.
+    fn taken_from(found: &[i64], numbers: &[i64]) -> bool {
+        let mut rest = numbers.to_vec();
+        found
+            .iter()
+            .all(|n| match rest.iter().position(|m| m == n) {
+                Some(i) => {
+                    rest.swap_remove(i);
+                    true
+                }
+                None => false,
+            })
     }
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 165080960);
+    proptest! {
+        #[test]
+        fn pairs_sum_to_target(
+            numbers in prop::collection::vec(0i64..3000, 0..40),
+            target in 0i64..4000,
+        ) {
+            let pair = pair_summing_to(&numbers, target);
+            for found in [pair, pair_summing_to_hashed(&numbers, target)] {
+                prop_assert_eq!(found.is_some(), pair.is_some());
+                if let Some((a, b)) = found {
+                    prop_assert_eq!(a + b, target);
+                    prop_assert!(taken_from(&[a, b], &numbers));
+                }
+            }
+        }
+
+        #[test]
+        fn triples_sum_to_target(
+            numbers in prop::collection::vec(0i64..3000, 0..25),
+            target in 0i64..6000,
+        ) {
+            let triple = triple_summing_to(&numbers, target);
+            for found in [triple, triple_summing_to_hashed(&numbers, target)] {
+                prop_assert_eq!(found.is_some(), triple.is_some());
+                if let Some((a, b, c)) = found {
+                    prop_assert_eq!(a + b + c, target);
+                    prop_assert!(taken_from(&[a, b, c], &numbers));
+                }
+            }
+        }
+
     }
 }
@@ -0,0 +1,34 @@
+// Tries every pair and triple of entries, assuming there's exactly one of
+// each that sums to 2020.
+
+fn entries(input: &str) -> Vec<i64> {
+    input.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+pub fn part_1(input: &str) -> i64 {
+    let entries = entries(input);
+    let mut answer = None;
+    for i in 0..entries.len() {
+        for j in i + 1..entries.len() {
+            if entries[i] + entries[j] == 2020 {
+                answer = Some(entries[i] * entries[j]);
+            }
+        }
+    }
+    answer.unwrap()
+}
+
+pub fn part_2(input: &str) -> i64 {
+    let entries = entries(input);
+    let mut answer = None;
+    for i in 0..entries.len() {
+        for j in i + 1..entries.len() {
+            for k in j + 1..entries.len() {
+                if entries[i] + entries[j] + entries[k] == 2020 {
+                    answer = Some(entries[i] * entries[j] * entries[k]);
+                }
+            }
+        }
+    }
+    answer.unwrap()
+}
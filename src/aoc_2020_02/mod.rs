@@ -1,72 +1,133 @@
-use serde::Deserialize;
-use serde_scan::scan;
+use crate::format::FormatError;
+use crate::input;
+use crate::parse::ParseError;
+use crate::registry::aoc_day;
+use crate::rng::Rng;
+use crate::AocParse;
+
+mod oracle;
 
 // https://adventofcode.com/2020/day/2
 
-#[derive(Debug, Deserialize)]
-struct PasswordRecord {
+#[derive(Debug, AocParse)]
+#[aoc(format = "{min}-{max} {letter}: {password}")]
+pub struct PasswordRecord {
     min: usize,
     max: usize,
     letter: char,
     password: String,
 }
 
-pub fn part_1(input: &str) -> usize {
-    let recs = input.trim().lines().map(|l| {
-        let rec: PasswordRecord = scan!("{}-{} {}: {}" <- l).unwrap();
-        rec
-    });
-
-    recs.filter(|r| {
-        let count = r.password.matches(r.letter).count();
-        r.min <= count && count <= r.max
-    })
-    .count()
+pub fn parse(input: &str) -> Result<Vec<PasswordRecord>, ParseError> {
+    input::numbered_lines(input)
+        .map(|(line, l)| l.parse().map_err(|e: FormatError| e.on_line(line)))
+        .collect()
 }
 
-pub fn part_2(input: &str) -> usize {
-    let recs = input.trim().lines().map(|l| {
-        let rec: PasswordRecord = scan!("{}-{} {}: {}" <- l).unwrap();
-        rec
-    });
-
-    recs.filter(|r| {
-        let a = r.password.chars().nth(r.min - 1).unwrap();
-        let b = r.password.chars().nth(r.max - 1).unwrap();
-        (a == r.letter) ^ (b == r.letter)
-    })
-    .count()
+pub fn part_1(records: &[PasswordRecord]) -> usize {
+    records
+        .iter()
+        .filter(|r| {
+            let count = r.password.matches(r.letter).count();
+            r.min <= count && count <= r.max
+        })
+        .count()
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
-
-#[cfg(test)]
-mod part_1 {
-    use super::*;
+/// Positions are one-based, and a position outside the password never
+/// matches.
+pub fn part_2(records: &[PasswordRecord]) -> usize {
+    records
+        .iter()
+        .filter(|r| {
+            let at = |position: usize| {
+                position
+                    .checked_sub(1)
+                    .and_then(|i| r.password.chars().nth(i))
+                    == Some(r.letter)
+            };
+            at(r.min) ^ at(r.max)
+        })
+        .count()
+}
 
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 2);
-    }
+/// `size` records with passwords of a few common letters, so that the
+/// policy letter shows up a varying number of times. Like in the real ones,
+/// the two numbers are different positions in the password.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let len = rng.between(2, 20);
+            let min = rng.between(1, len - 1);
+            let max = rng.between(min + 1, len);
+            let letters = b"abcde";
+            let letter = letters[rng.below(5) as usize] as char;
+            let password = (0..len)
+                .map(|_| letters[rng.below(5) as usize] as char)
+                .collect::<String>();
+            format!("{}-{} {}: {}\n", min, max, letter, password)
+        })
+        .collect()
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 600);
-    }
+aoc_day! {
+    year: 2020,
+    day: 2,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 2, answer: 600 },
+    part_2: { example: 1, answer: 245 },
 }
 
 #[cfg(test)]
-mod part_2 {
+mod properties {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 1);
-    }
+    proptest! {
+        #[test]
+        fn records_round_trip(
+            min in 1usize..30,
+            max in 1usize..30,
+            letter in "[a-z]",
+            password in "[a-z]{1,30}",
+        ) {
+            let line = format!("{}-{} {}: {}", min, max, letter, password);
+            let record = line.parse::<PasswordRecord>().unwrap();
+            prop_assert_eq!(record.min, min);
+            prop_assert_eq!(record.max, max);
+            prop_assert_eq!(record.letter.to_string(), letter);
+            prop_assert_eq!(record.password, password);
+        }
+
+        #[test]
+        fn count_policy_matches_occurrences(
+            min in 1usize..10,
+            extra in 0usize..10,
+            count in 0usize..20,
+            filler in "[b-z]{0,10}",
+        ) {
+            let password = format!("{}{}", "a".repeat(count), filler);
+            prop_assume!(!password.is_empty());
+            let line = format!("{}-{} a: {}", min, min + extra, password);
+            let valid = min <= count && count <= min + extra;
+            prop_assert_eq!(part_1(&parse(&line).unwrap()), usize::from(valid));
+        }
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 245);
+        #[test]
+        fn position_policy_needs_exactly_one_match(
+            (password, first, second) in "[ab]{2,20}".prop_flat_map(|password| {
+                let len = password.len();
+                (Just(password), 1..len).prop_flat_map(move |(password, first)| {
+                    (Just(password), Just(first), first + 1..=len)
+                })
+            }),
+        ) {
+            let line = format!("{}-{} a: {}", first, second, password);
+            let bytes = password.as_bytes();
+            let valid = (bytes[first - 1] == b'a') != (bytes[second - 1] == b'a');
+            prop_assert_eq!(part_2(&parse(&line).unwrap()), usize::from(valid));
+        }
     }
 }
@@ -0,0 +1,48 @@
+// Takes each record apart by hand and checks the policies letter by letter.
+
+/// The numbers, letter and password of a `1-3 a: abcde` record.
+fn record(line: &str) -> (usize, usize, char, Vec<char>) {
+    let (policy, password) = line.split_once(": ").unwrap();
+    let (range, letter) = policy.split_once(' ').unwrap();
+    let (min, max) = range.split_once('-').unwrap();
+    (
+        min.parse().unwrap(),
+        max.parse().unwrap(),
+        letter.chars().next().unwrap(),
+        password.chars().collect(),
+    )
+}
+
+pub fn part_1(input: &str) -> usize {
+    let mut valid = 0;
+    for line in input.lines() {
+        let (min, max, letter, password) = record(line);
+        let mut count = 0;
+        for c in password {
+            if c == letter {
+                count += 1;
+            }
+        }
+        if min <= count && count <= max {
+            valid += 1;
+        }
+    }
+    valid
+}
+
+pub fn part_2(input: &str) -> usize {
+    let mut valid = 0;
+    for line in input.lines() {
+        let (first, second, letter, password) = record(line);
+        let mut matches = 0;
+        for (i, c) in password.into_iter().enumerate() {
+            if (i + 1 == first || i + 1 == second) && c == letter {
+                matches += 1;
+            }
+        }
+        if matches == 1 {
+            valid += 1;
+        }
+    }
+    valid
+}
@@ -1,16 +1,24 @@
 extern crate itertools;
 
+use crate::input;
+use crate::overflow::{checked_product, Overflow};
+use crate::registry::aoc_day;
+use crate::rng::Rng;
 use itertools::iterate;
 
+mod oracle;
+
 // https://adventofcode.com/2020/day/3
 
 fn count_trees(input: &str, right: usize, down: usize) -> usize {
-    let rows: Vec<&str> = input.trim().lines().collect();
-    let width = rows.first().unwrap().len();
-    let height = rows.len();
+    let grid = input::grid(input);
+    let width = grid.width();
+    if width == 0 {
+        return 0;
+    }
     iterate((0, 0), |(x, y)| ((x + right) % width, y + down))
-        .take_while(|&(_, y)| y < height)
-        .filter(|&(x, y)| rows[y].chars().nth(x).unwrap() == '#')
+        .take_while(|&(_, y)| y < grid.height())
+        .filter(|&(x, y)| grid.get(x, y) == Some(b'#'))
         .count()
 }
 
@@ -18,43 +26,75 @@ pub fn part_1(input: &str) -> usize {
     count_trees(input, 3, 1)
 }
 
-pub fn part_2(input: &str) -> usize {
-    count_trees(input, 1, 1)
-        * count_trees(input, 3, 1)
-        * count_trees(input, 5, 1)
-        * count_trees(input, 7, 1)
-        * count_trees(input, 1, 2)
+pub fn part_2(input: &str) -> Result<u64, Overflow> {
+    checked_product(
+        [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+            .map(|(right, down)| count_trees(input, right, down) as u64),
+    )
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
-
-#[cfg(test)]
-mod part_1 {
-    use super::*;
-
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 7);
-    }
+/// A map `size` rows high and 31 columns wide, like the real ones, with
+/// trees on about a quarter of the squares.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let row = (0..31)
+                .map(|_| if rng.chance(1, 4) { '#' } else { '.' })
+                .collect::<String>();
+            row + "\n"
+        })
+        .collect()
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 286);
-    }
+aoc_day! {
+    year: 2020,
+    day: 3,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 7, answer: 286 },
+    part_2: { example: 336, answer: 3638606400 },
 }
 
 #[cfg(test)]
-mod part_2 {
+mod properties {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 336);
+    fn map() -> impl Strategy<Value = Vec<String>> {
+        (1usize..20).prop_flat_map(|width| {
+            prop::collection::vec(
+                prop::collection::vec(prop::bool::ANY, width)
+                    .prop_map(|row| row.iter().map(|&t| if t { '#' } else { '.' }).collect()),
+                1..40,
+            )
+        })
     }
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 3638606400);
+    proptest! {
+        #[test]
+        fn trees_are_bounded_by_visited_rows(
+            rows in map(),
+            right in 0usize..10,
+            down in 1usize..4,
+        ) {
+            let visited = rows.len().div_ceil(down);
+            let input = rows.join("\n");
+            prop_assert!(count_trees(&input, right, down) <= visited);
+
+            let forest = rows.iter().map(|r| r.replace('.', "#")).collect::<Vec<_>>();
+            prop_assert_eq!(count_trees(&forest.join("\n"), right, down), visited);
+            let open = rows.iter().map(|r| r.replace('#', ".")).collect::<Vec<_>>();
+            prop_assert_eq!(count_trees(&open.join("\n"), right, down), 0);
+        }
+
+        #[test]
+        fn part_2_is_product_of_slopes(rows in map()) {
+            let input = rows.join("\n");
+            let product = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+                .iter()
+                .map(|&(right, down)| count_trees(&input, right, down) as u64)
+                .product::<u64>();
+            prop_assert_eq!(part_2(&input), Ok(product));
+        }
     }
 }
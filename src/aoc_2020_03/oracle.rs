@@ -0,0 +1,33 @@
+// Repeats the map to the right until it's wide enough for the whole slope, as
+// the puzzle describes it, and walks down it.
+
+use crate::overflow::Overflow;
+
+fn trees(rows: &[&str], right: usize, down: usize) -> u64 {
+    let mut trees = 0;
+    let mut x = 0;
+    for row in rows.iter().step_by(down) {
+        let repeated = row.repeat(x / row.len() + 1);
+        if repeated.as_bytes()[x] == b'#' {
+            trees += 1;
+        }
+        x += right;
+    }
+    trees
+}
+
+pub fn part_1(input: &str) -> u64 {
+    let rows = input.lines().collect::<Vec<_>>();
+    trees(&rows, 3, 1)
+}
+
+/// The product is taken as `u128`, which holds it for any map of fewer than
+/// 2^25 rows.
+pub fn part_2(input: &str) -> Result<u64, Overflow> {
+    let rows = input.lines().collect::<Vec<_>>();
+    let mut product = 1u128;
+    for (right, down) in [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)] {
+        product *= trees(&rows, right, down) as u128;
+    }
+    u64::try_from(product).map_err(|_| Overflow)
+}
@@ -1,3 +1,12 @@
+use crate::input;
+use crate::parse::{
+    delimited, many0, parse_all, tag, take_while, take_while1, whitespace, ParseError, Parser,
+};
+use crate::registry::aoc_day;
+use crate::rng::Rng;
+
+mod oracle;
+
 // https://adventofcode.com/2020/day/4
 
 struct Passport<'a> {
@@ -23,23 +32,33 @@ struct Passport<'a> {
     pid: &'a str,
 }
 
-fn get_field<'a>(s: &'a str, field: &str) -> Option<&'a str> {
-    s.split_whitespace()
-        .find(|part| part.starts_with(field))
-        .unwrap_or("")
-        .strip_prefix(field)
-        .unwrap_or("")
-        .strip_prefix(":")
+/// The `key:value` pairs of a passport, in the order they're given.
+pub type Fields<'a> = Vec<(&'a str, &'a str)>;
+
+/// The fields of a passport, separated by any whitespace. Tokens that aren't
+/// `key:value` pairs can't be a required field, so they're skipped.
+fn parse_fields(s: &str) -> Result<Fields<'_>, ParseError> {
+    let key = take_while1(|c| c.is_ascii_alphanumeric(), "a field name");
+    let value = take_while(|c| !c.is_whitespace());
+    let field = key.skip(tag(":")).then(value).map(Some);
+    let other = take_while1(|c| !c.is_whitespace(), "a field").map(|_| None);
+    let token = field.or(other).skip(whitespace());
+    parse_all(s, delimited(whitespace(), many0(token), whitespace()))
+        .map(|tokens| tokens.into_iter().flatten().collect())
+}
+
+fn get_field<'a>(fields: &[(&str, &'a str)], field: &str) -> Option<&'a str> {
+    fields.iter().find(|(k, _)| *k == field).map(|(_, v)| *v)
 }
 
-fn try_create_passport(s: &str) -> Option<Passport> {
-    let byr = get_field(s, "byr");
-    let iyr = get_field(s, "iyr");
-    let eyr = get_field(s, "eyr");
-    let hgt = get_field(s, "hgt");
-    let hcl = get_field(s, "hcl");
-    let ecl = get_field(s, "ecl");
-    let pid = get_field(s, "pid");
+fn try_create_passport<'a>(fields: &[(&str, &'a str)]) -> Option<Passport<'a>> {
+    let byr = get_field(fields, "byr");
+    let iyr = get_field(fields, "iyr");
+    let eyr = get_field(fields, "eyr");
+    let hgt = get_field(fields, "hgt");
+    let hcl = get_field(fields, "hcl");
+    let ecl = get_field(fields, "ecl");
+    let pid = get_field(fields, "pid");
     match (byr, iyr, eyr, hgt, hcl, ecl, pid) {
         (Some(byr), Some(iyr), Some(eyr), Some(hgt), Some(hcl), Some(ecl), Some(pid)) => {
             Some(Passport {
@@ -77,11 +96,10 @@ fn validate_height(hgt: &str) -> bool {
 fn validate_hair_color(hcl: &str) -> bool {
     hcl.len() == 7
         && hcl.starts_with('#')
-        && hcl.chars().skip(1).all(|c| match c {
-            '0'..='9' => true,
-            'a'..='f' => true,
-            _ => false,
-        })
+        && hcl
+            .chars()
+            .skip(1)
+            .all(|c| matches!(c, '0'..='9' | 'a'..='f'))
 }
 
 fn validate_eye_color(ecl: &str) -> bool {
@@ -95,167 +113,294 @@ fn validate_eye_color(ecl: &str) -> bool {
 }
 
 fn validate_passport_id(pid: &str) -> bool {
-    pid.len() == 9
-        && pid.chars().all(|c| match c {
-            '0'..='9' => true,
-            _ => false,
-        })
+    pid.len() == 9 && pid.chars().all(|c| c.is_ascii_digit())
 }
 
 fn validate_passport(passport: &Passport) -> bool {
     // - byr (Birth Year) - four digits; at least 1920 and at most 2002.
-    if validate_int_range(passport.byr, 1920, 2002) == false {
+    if !validate_int_range(passport.byr, 1920, 2002) {
         return false;
     }
 
     // - iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-    if validate_int_range(passport.iyr, 2010, 2020) == false {
+    if !validate_int_range(passport.iyr, 2010, 2020) {
         return false;
     }
 
     // - eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-    if validate_int_range(passport.eyr, 2020, 2030) == false {
+    if !validate_int_range(passport.eyr, 2020, 2030) {
         return false;
     }
 
     // - hgt (Height) - a number followed by either cm or in:
     //   - If cm, the number must be at least 150 and at most 193.
     //   - If in, the number must be at least 59 and at most 76.
-    if validate_height(passport.hgt) == false {
+    if !validate_height(passport.hgt) {
         return false;
     }
 
     // - hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-    if validate_hair_color(passport.hcl) == false {
+    if !validate_hair_color(passport.hcl) {
         return false;
     }
 
     // - ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-    if validate_eye_color(passport.ecl) == false {
+    if !validate_eye_color(passport.ecl) {
         return false;
     }
 
     // - pid (Passport ID) - a nine-digit number, including leading zeroes.
-    if validate_passport_id(passport.pid) == false {
+    if !validate_passport_id(passport.pid) {
         return false;
     }
 
     true
 }
 
-pub fn part_1(input: &str) -> usize {
-    input
-        .trim()
-        .split("\n\n")
-        .filter_map(|l| try_create_passport(l))
+/// The fields of every passport in a batch.
+pub fn parse(input: &str) -> Result<Vec<Fields<'_>>, ParseError> {
+    input::numbered_blocks(input)
+        .map(|(line, block)| parse_fields(block).map_err(|e| e.starting_at_line(line)))
+        .collect()
+}
+
+pub fn part_1(passports: &[Fields]) -> usize {
+    passports
+        .iter()
+        .filter_map(|p| try_create_passport(p))
         .count()
 }
 
-pub fn part_2(input: &str) -> usize {
-    input
-        .trim()
-        .split("\n\n")
-        .filter_map(|l| try_create_passport(l))
+pub fn part_2(passports: &[Fields]) -> usize {
+    passports
+        .iter()
+        .filter_map(|p| try_create_passport(p))
         .filter(|p| validate_passport(p))
         .count()
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
-
-#[cfg(test)]
-mod part_1 {
-    use super::*;
-
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 2);
-    }
-
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 233);
+/// A value for `key` that's valid three times out of four.
+fn generate_value(rng: &mut Rng, key: &str) -> String {
+    let valid = rng.chance(3, 4);
+    let year = |rng: &mut Rng, lo: u64, hi: u64| {
+        if valid {
+            rng.between(lo, hi).to_string()
+        } else if rng.chance(1, 2) {
+            rng.between(1900, 2040).to_string()
+        } else {
+            rng.between(10, 99).to_string()
+        }
+    };
+    match key {
+        "byr" => year(rng, 1920, 2002),
+        "iyr" => year(rng, 2010, 2020),
+        "eyr" => year(rng, 2020, 2030),
+        "hgt" => match (valid, rng.below(3)) {
+            (true, 0) => format!("{}in", rng.between(59, 76)),
+            (true, _) => format!("{}cm", rng.between(150, 193)),
+            (false, 0) => format!("{}in", rng.between(40, 100)),
+            (false, 1) => format!("{}cm", rng.between(100, 250)),
+            (false, _) => rng.between(59, 193).to_string(),
+        },
+        "hcl" => {
+            let digits = if valid {
+                b"0123456789abcdef"
+            } else {
+                b"0123456789abcdez"
+            };
+            let color = (0..6)
+                .map(|_| digits[rng.below(16) as usize] as char)
+                .collect::<String>();
+            match (valid, rng.below(2)) {
+                (false, 0) => color,
+                _ => format!("#{}", color),
+            }
+        }
+        "ecl" => {
+            let colors: &[&str] = if valid {
+                &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
+            } else {
+                &["red", "xry", "gr", "blue", "zzz"]
+            };
+            colors[rng.below(colors.len() as u64) as usize].to_string()
+        }
+        "pid" => {
+            let len = if valid { 9 } else { rng.between(8, 10) };
+            (0..len).map(|_| rng.below(10).to_string()).collect()
+        }
+        _ => rng.between(100, 350).to_string(),
     }
 }
 
-#[cfg(test)]
-mod part_2 {
-    use super::*;
-
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 2);
-    }
+/// A batch of `size` passports. Each field is missing one time in ten, and
+/// their values are invalid one time in four (see `generate_value`).
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let passports = (0..size)
+        .map(|_| {
+            let mut fields = Vec::new();
+            for key in ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"] {
+                if !rng.chance(1, 10) {
+                    fields.push(format!("{}:{}", key, generate_value(rng, key)));
+                }
+            }
+            rng.shuffle(&mut fields);
+            let mut passport = String::new();
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    passport.push(if rng.chance(1, 3) { '\n' } else { ' ' });
+                }
+                passport.push_str(field);
+            }
+            passport + "\n"
+        })
+        .collect::<Vec<_>>();
+    passports.join("\n")
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 111);
-    }
+aoc_day! {
+    year: 2020,
+    day: 4,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 2, answer: 233 },
+    part_2: { example: 2, answer: 111 },
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("ecl:gry pid:860033327\r\nhcl:#fffffd\n"),
+            Ok(vec![
+                ("ecl", "gry"),
+                ("pid", "860033327"),
+                ("hcl", "#fffffd")
+            ])
+        );
+        assert_eq!(parse_fields(""), Ok(vec![]));
+        assert_eq!(
+            parse_fields("ecl:gry pid -:x hgt:"),
+            Ok(vec![("ecl", "gry"), ("hgt", "")])
+        );
+    }
+
     #[test]
     fn test_validate_int_range() {
-        assert_eq!(validate_int_range("", 1, 5), false);
-        assert_eq!(validate_int_range("garbage", 1, 5), false);
-        assert_eq!(validate_int_range("0", 1, 5), false);
-        assert_eq!(validate_int_range("1", 1, 5), true);
-        assert_eq!(validate_int_range("2", 1, 5), true);
-        assert_eq!(validate_int_range("3", 1, 5), true);
-        assert_eq!(validate_int_range("4", 1, 5), true);
-        assert_eq!(validate_int_range("5", 1, 5), true);
-        assert_eq!(validate_int_range("6", 1, 5), false);
+        assert!(!validate_int_range("", 1, 5));
+        assert!(!validate_int_range("garbage", 1, 5));
+        assert!(!validate_int_range("0", 1, 5));
+        assert!(validate_int_range("1", 1, 5));
+        assert!(validate_int_range("2", 1, 5));
+        assert!(validate_int_range("3", 1, 5));
+        assert!(validate_int_range("4", 1, 5));
+        assert!(validate_int_range("5", 1, 5));
+        assert!(!validate_int_range("6", 1, 5));
     }
 
     #[test]
     fn test_validate_height() {
-        assert_eq!(validate_height(""), false);
-        assert_eq!(validate_height("garbage"), false);
-
-        assert_eq!(validate_height("149cm"), false);
-        assert_eq!(validate_height("150cm"), true);
-        assert_eq!(validate_height("193cm"), true);
-        assert_eq!(validate_height("194cm"), false);
-
-        assert_eq!(validate_height("58in"), false);
-        assert_eq!(validate_height("59in"), true);
-        assert_eq!(validate_height("76in"), true);
-        assert_eq!(validate_height("77in"), false);
+        assert!(!validate_height(""));
+        assert!(!validate_height("garbage"));
+
+        assert!(!validate_height("149cm"));
+        assert!(validate_height("150cm"));
+        assert!(validate_height("193cm"));
+        assert!(!validate_height("194cm"));
+
+        assert!(!validate_height("58in"));
+        assert!(validate_height("59in"));
+        assert!(validate_height("76in"));
+        assert!(!validate_height("77in"));
     }
 
     #[test]
     fn test_validate_hair_color() {
-        assert_eq!(validate_hair_color(""), false);
-        assert_eq!(validate_hair_color("garbage"), false);
-        assert_eq!(validate_hair_color("#xxxxxx"), false);
-        assert_eq!(validate_hair_color("#12345"), false);
-        assert_eq!(validate_hair_color("#1234567"), false);
-        assert_eq!(validate_hair_color("#123456"), true);
-        assert_eq!(validate_hair_color("#abcdef"), true);
+        assert!(!validate_hair_color(""));
+        assert!(!validate_hair_color("garbage"));
+        assert!(!validate_hair_color("#xxxxxx"));
+        assert!(!validate_hair_color("#12345"));
+        assert!(!validate_hair_color("#1234567"));
+        assert!(validate_hair_color("#123456"));
+        assert!(validate_hair_color("#abcdef"));
     }
 
     #[test]
     fn test_validate_eye_color() {
-        assert_eq!(validate_eye_color(""), false);
-        assert_eq!(validate_eye_color("xxx"), false);
-        assert_eq!(validate_eye_color("amb"), true);
-        assert_eq!(validate_eye_color("blu"), true);
-        assert_eq!(validate_eye_color("brn"), true);
-        assert_eq!(validate_eye_color("gry"), true);
-        assert_eq!(validate_eye_color("grn"), true);
-        assert_eq!(validate_eye_color("hzl"), true);
-        assert_eq!(validate_eye_color("oth"), true);
+        assert!(!validate_eye_color(""));
+        assert!(!validate_eye_color("xxx"));
+        assert!(validate_eye_color("amb"));
+        assert!(validate_eye_color("blu"));
+        assert!(validate_eye_color("brn"));
+        assert!(validate_eye_color("gry"));
+        assert!(validate_eye_color("grn"));
+        assert!(validate_eye_color("hzl"));
+        assert!(validate_eye_color("oth"));
     }
 
     #[test]
     fn test_validate_passport_id() {
-        assert_eq!(validate_passport_id(""), false);
-        assert_eq!(validate_passport_id("12345678"), false);
-        assert_eq!(validate_passport_id("1234567890"), false);
-        assert_eq!(validate_passport_id("123456789"), true);
-        assert_eq!(validate_passport_id("12345678a"), false);
+        assert!(!validate_passport_id(""));
+        assert!(!validate_passport_id("12345678"));
+        assert!(!validate_passport_id("1234567890"));
+        assert!(validate_passport_id("123456789"));
+        assert!(!validate_passport_id("12345678a"));
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn fields_round_trip(
+            fields in prop::collection::vec(("[a-z]{3}", "[!-~]{0,12}"), 0..10),
+            separators in prop::collection::vec(prop::sample::select(vec![" ", "\n", "\r\n", "  "]), 10),
+        ) {
+            let text = fields
+                .iter()
+                .zip(&separators)
+                .map(|((key, value), sep)| format!("{}:{}{}", key, value, sep))
+                .collect::<String>();
+            let parsed = parse_fields(&text).unwrap();
+            let expected = fields
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(parsed, expected);
+        }
+
+        #[test]
+        fn int_ranges_are_inclusive(n in -10i32..3000, lo in 0i32..2000, len in 0i32..100) {
+            let hi = lo + len;
+            prop_assert_eq!(validate_int_range(&n.to_string(), lo, hi), lo <= n && n <= hi);
+        }
+
+        #[test]
+        fn heights_need_a_unit(n in 0i32..300, unit in "(cm|in|mm|)") {
+            let valid = match unit.as_str() {
+                "cm" => (150..=193).contains(&n),
+                "in" => (59..=76).contains(&n),
+                _ => false,
+            };
+            prop_assert_eq!(validate_height(&format!("{}{}", n, unit)), valid);
+        }
+
+        #[test]
+        fn hair_colors_are_lowercase_hex(color in "#[0-9a-fA-F]{5,7}") {
+            let valid = color.len() == 7 && !color.chars().any(|c| c.is_ascii_uppercase());
+            prop_assert_eq!(validate_hair_color(&color), valid);
+        }
+
+        #[test]
+        fn passport_ids_are_nine_digits(pid in "[0-9a]{7,11}") {
+            let valid = pid.len() == 9 && !pid.contains('a');
+            prop_assert_eq!(validate_passport_id(&pid), valid);
+        }
     }
 }
@@ -0,0 +1,88 @@
+// Checks each passport against the puzzle's rules as literally as possible.
+
+const REQUIRED: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+/// The `key:value` pairs of every passport, which are separated by blank
+/// lines.
+fn passports(input: &str) -> Vec<Vec<(String, String)>> {
+    let mut passports = vec![Vec::new()];
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            passports.push(Vec::new());
+            continue;
+        }
+        for field in line.split_whitespace() {
+            let (key, value) = field.split_once(':').unwrap();
+            let passport = passports.last_mut().unwrap();
+            passport.push((key.to_string(), value.to_string()));
+        }
+    }
+    passports.retain(|p| !p.is_empty());
+    passports
+}
+
+fn value<'a>(passport: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    for (k, v) in passport {
+        if k == key {
+            return Some(v);
+        }
+    }
+    None
+}
+
+fn has_required_fields(passport: &[(String, String)]) -> bool {
+    REQUIRED.iter().all(|key| value(passport, key).is_some())
+}
+
+fn digits(s: &str, count: usize) -> bool {
+    s.len() == count && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `s` is a number of `count` digits between `lo` and `hi`.
+fn number(s: &str, count: usize, lo: u32, hi: u32) -> bool {
+    digits(s, count) && (lo..=hi).contains(&s.parse().unwrap())
+}
+
+fn valid(key: &str, value: &str) -> bool {
+    match key {
+        "byr" => number(value, 4, 1920, 2002),
+        "iyr" => number(value, 4, 2010, 2020),
+        "eyr" => number(value, 4, 2020, 2030),
+        "hgt" => {
+            if let Some(cm) = value.strip_suffix("cm") {
+                (1..=3).any(|count| number(cm, count, 150, 193))
+            } else if let Some(inches) = value.strip_suffix("in") {
+                (1..=2).any(|count| number(inches, count, 59, 76))
+            } else {
+                false
+            }
+        }
+        "hcl" => {
+            value.len() == 7
+                && value.starts_with('#')
+                && value[1..].bytes().all(|b| b"0123456789abcdef".contains(&b))
+        }
+        "ecl" => ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&value),
+        "pid" => digits(value, 9),
+        _ => true,
+    }
+}
+
+pub fn part_1(input: &str) -> usize {
+    passports(input)
+        .iter()
+        .filter(|p| has_required_fields(p))
+        .count()
+}
+
+pub fn part_2(input: &str) -> usize {
+    passports(input)
+        .iter()
+        .filter(|p| has_required_fields(p))
+        .filter(|p| {
+            REQUIRED
+                .iter()
+                .all(|key| valid(key, value(p, key).unwrap()))
+        })
+        .count()
+}
@@ -1,14 +1,21 @@
 extern crate itertools;
 
+use crate::input;
+use crate::parse::{char_if, parse_all, times, ParseError, Parser};
+use crate::registry::aoc_day;
+use crate::rng::Rng;
 use itertools::Itertools;
+use std::fmt;
+
+mod oracle;
 
 // https://adventofcode.com/2020/day/5
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Seat {
-    row: u32,
-    col: u32,
-    id: u32,
+pub struct Seat {
+    pub row: u32,
+    pub col: u32,
+    pub id: u32,
 }
 
 fn calculate_seat_id(row: u32, col: u32) -> u32 {
@@ -45,71 +52,155 @@ fn get_col_range(s: &str) -> (u32, u32) {
     })
 }
 
-fn seat_from_str(s: &str) -> Seat {
+fn validate_pass(s: &str) -> Result<(), ParseError> {
+    let row_half = char_if(|c| c == 'F' || c == 'B', "`F` or `B`");
+    let col_half = char_if(|c| c == 'L' || c == 'R', "`L` or `R`");
+    parse_all(s, times(7, row_half).then(times(3, col_half)))?;
+    Ok(())
+}
+
+/// Decodes a boarding pass that has already been validated.
+fn decode_seat(s: &str) -> Seat {
     let row = get_row_range(s).0;
     let col = get_col_range(s).0;
     let id = calculate_seat_id(row, col);
     Seat { row, col, id }
 }
 
-pub fn part_1(input: &str) -> u32 {
-    input
-        .trim()
-        .lines()
-        .map(|s| seat_from_str(s).id)
-        .max()
-        .unwrap_or(0)
+/// The seat ID of a validated boarding pass, read as a 10-bit binary number
+/// where `B` and `R` are ones.
+fn decode_seat_id_bits(s: &str) -> u32 {
+    s.bytes()
+        .fold(0, |id, b| (id << 1) | u32::from(b == b'B' || b == b'R'))
 }
 
-pub fn part_2(input: &str) -> u32 {
-    let mut vec = input
-        .trim()
-        .lines()
-        .map(|s| seat_from_str(s))
-        .collect::<Vec<_>>();
+/// Decodes a boarding pass such as `FBFBBFFRLR`.
+pub fn seat_from_str(s: &str) -> Result<Seat, ParseError> {
+    validate_pass(s)?;
+    Ok(decode_seat(s))
+}
+
+/// Same as `seat_from_str`, but only decodes the ID, as a binary number.
+pub fn seat_id_bits(s: &str) -> Result<u32, ParseError> {
+    validate_pass(s)?;
+    Ok(decode_seat_id_bits(s))
+}
+
+/// The boarding pass for a seat ID.
+pub fn encode_seat(id: u32) -> String {
+    (0..10)
+        .rev()
+        .map(|bit| {
+            let one = id >> bit & 1 == 1;
+            match (bit >= 3, one) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            }
+        })
+        .collect()
+}
+
+/// There's no free seat with taken seats on both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeatNotFound;
+
+impl fmt::Display for SeatNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no free seat between two taken ones")
+    }
+}
+
+impl std::error::Error for SeatNotFound {}
+
+/// The boarding passes, validated but not decoded so the parts can decode
+/// them in their own way.
+pub fn parse(input: &str) -> Result<Vec<&str>, ParseError> {
+    input::numbered_lines(input)
+        .map(|(line, pass)| {
+            validate_pass(pass)
+                .map(|_| pass)
+                .map_err(|e| e.starting_at_line(line))
+        })
+        .collect()
+}
+
+pub fn part_1(passes: &[&str]) -> u32 {
+    passes.iter().map(|s| decode_seat(s).id).max().unwrap_or(0)
+}
+
+pub fn part_2(passes: &[&str]) -> Result<u32, SeatNotFound> {
+    let mut vec = passes.iter().map(|s| decode_seat(s)).collect::<Vec<_>>();
 
     vec.sort_by_key(|s| s.id);
 
     for (a, b) in vec.iter().tuple_windows() {
         if (a.id + 2) == b.id {
-            return a.id + 1;
+            return Ok(a.id + 1);
         }
     }
-    panic!("Didn't find the seat!");
+    Err(SeatNotFound)
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
-
-#[cfg(test)]
-mod part_1 {
-    use super::*;
-
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 820);
-    }
+pub fn part_1_bits(passes: &[&str]) -> u32 {
+    passes
+        .iter()
+        .map(|s| decode_seat_id_bits(s))
+        .max()
+        .unwrap_or(0)
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 989);
+/// Same as `part_2`, but without sorting: the IDs that were seen are marked
+/// in a bitset, and the seat is the first unmarked ID between two marked
+/// ones. Like `part_2`, a pass that shows up twice doesn't matter.
+pub fn part_2_bits(passes: &[&str]) -> Result<u32, SeatNotFound> {
+    let mut seen = [0u64; 16];
+    for id in passes.iter().map(|s| decode_seat_id_bits(s)) {
+        seen[id as usize / 64] |= 1 << (id % 64);
     }
+    let is_seen = |id: u32| seen[id as usize / 64] >> (id % 64) & 1 == 1;
+    (1..1023)
+        .find(|&id| !is_seen(id) && is_seen(id - 1) && is_seen(id + 1))
+        .ok_or(SeatNotFound)
 }
 
-#[cfg(test)]
-mod part_2 {
-    use super::*;
+/// A shuffled run of `size` consecutive seats (at least 2, and at most all
+/// but one seat of the plane) with one seat missing in between.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let taken = size.clamp(2, 1023) as u64;
+    let first = rng.between(0, 1023 - taken) as u32;
+    let last = first + taken as u32;
+    let missing = rng.between(first as u64 + 1, last as u64 - 1) as u32;
+    let mut passes = (first..=last)
+        .filter(|&id| id != missing)
+        .map(encode_seat)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut passes);
+    passes.iter().map(|p| format!("{}\n", p)).collect()
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 548);
-    }
+aoc_day! {
+    year: 2020,
+    day: 5,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 820, answer: 989, alternatives: [part_1_bits] },
+    part_2: { answer: 548, alternatives: [part_2_bits] },
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encode_seat() {
+        assert_eq!(encode_seat(357), "FBFBBFFRLR");
+        assert_eq!(seat_from_str(&encode_seat(820)).unwrap().id, 820);
+        assert_eq!(seat_id_bits(&encode_seat(820)), Ok(820));
+    }
+
     #[test]
     fn test_calculate_seat_id() {
         assert_eq!(calculate_seat_id(44, 5), 357);
@@ -142,27 +233,77 @@ mod tests {
     fn test_seat_from_str() {
         assert_eq!(
             seat_from_str("BFFFBBFRRR"),
-            Seat {
+            Ok(Seat {
                 row: 70,
                 col: 7,
                 id: 567
-            }
+            })
         );
         assert_eq!(
             seat_from_str("FFFBBBFRRR"),
-            Seat {
+            Ok(Seat {
                 row: 14,
                 col: 7,
                 id: 119
-            }
+            })
         );
         assert_eq!(
             seat_from_str("BBFFBBFRLL"),
-            Seat {
+            Ok(Seat {
                 row: 102,
                 col: 4,
                 id: 820
-            }
+            })
+        );
+        assert_eq!(
+            seat_from_str("BBFFBBFRL").map_err(|e| e.message),
+            Err("expected `L` or `R`".to_string())
         );
+        assert_eq!(seat_from_str("BBFFBBXRLL").map_err(|e| e.column), Err(7));
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn passes_round_trip(id in 0u32..1024) {
+            let pass = encode_seat(id);
+            prop_assert_eq!(
+                seat_from_str(&pass),
+                Ok(Seat {
+                    row: id / 8,
+                    col: id % 8,
+                    id
+                })
+            );
+            prop_assert_eq!(seat_id_bits(&pass), Ok(id));
+        }
+
+        #[test]
+        fn malformed_passes_are_rejected(pass in "[FBLRX]{0,12}") {
+            let valid = pass.len() == 10
+                && pass[..7].chars().all(|c| c == 'F' || c == 'B')
+                && pass[7..].chars().all(|c| c == 'L' || c == 'R');
+            prop_assert_eq!(seat_from_str(&pass).is_ok(), valid);
+            prop_assert_eq!(seat_id_bits(&pass).is_ok(), valid);
+        }
+
+        #[test]
+        fn missing_seat_is_found(first in 0u32..1000, len in 2u32..24, offset in 1u32..23) {
+            let last = (first + len).min(1023);
+            let missing = first + offset.min(last - first - 1);
+            let input = (first..=last)
+                .filter(|&id| id != missing)
+                .map(|id| encode_seat(id) + "\n")
+                .collect::<String>();
+            let passes = parse(&input).unwrap();
+            prop_assert_eq!(part_1(&passes), last);
+            prop_assert_eq!(part_2(&passes), Ok(missing));
+            prop_assert_eq!(part_2_bits(&passes), Ok(missing));
+        }
     }
 }
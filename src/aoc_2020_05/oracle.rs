@@ -0,0 +1,29 @@
+// Finds each pass's seat by trying every seat on the plane, and the free seat
+// by trying every seat with taken neighbours.
+
+/// Whether `pass` leads to the seat with `id`: each letter picks the upper
+/// (`B`, `R`) or lower (`F`, `L`) half, most significant bit first.
+fn leads_to(pass: &str, id: u32) -> bool {
+    pass.chars().enumerate().all(|(i, c)| {
+        let upper = id >> (9 - i) & 1 == 1;
+        upper == (c == 'B' || c == 'R')
+    })
+}
+
+fn seat_ids(input: &str) -> Vec<u32> {
+    input
+        .lines()
+        .map(|pass| (0..1024).find(|&id| leads_to(pass, id)).unwrap())
+        .collect()
+}
+
+pub fn part_1(input: &str) -> u32 {
+    seat_ids(input).into_iter().max().unwrap()
+}
+
+pub fn part_2(input: &str) -> u32 {
+    let ids = seat_ids(input);
+    (1..1023)
+        .find(|id| !ids.contains(id) && ids.contains(&(id - 1)) && ids.contains(&(id + 1)))
+        .unwrap()
+}
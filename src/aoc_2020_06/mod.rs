@@ -1,67 +1,115 @@
 extern crate itertools;
 
+use crate::input;
+use crate::registry::aoc_day;
+use crate::rng::Rng;
 use itertools::Itertools;
 
+mod oracle;
+
 // https://adventofcode.com/2020/day/6
 
-fn count_group_any(group: &str) -> usize {
+/// Counts the questions anyone in the group answered "yes" to.
+///
+/// Hash-based, so every character other than whitespace is a question, in
+/// any alphabet. Prefer `count_group_any_bits` when the answers are `a` to
+/// `z`.
+pub fn count_group_any(group: &str) -> usize {
     group
         .chars()
-        .filter(|c| c.is_ascii_lowercase())
+        .filter(|c| !c.is_whitespace())
         .unique()
         .count()
 }
 
-fn count_group_all(group: &str) -> usize {
+/// Counts the questions everyone in the group answered "yes" to.
+///
+/// Hash-based, so every character other than whitespace is a question, in
+/// any alphabet. Prefer `count_group_all_bits` when the answers are `a` to
+/// `z`.
+pub fn count_group_all(group: &str) -> usize {
     let people = group.lines().count();
     group
-        .chars()
-        .filter(char::is_ascii_lowercase)
+        .lines()
+        .flat_map(|person| person.chars().filter(|c| !c.is_whitespace()).unique())
         .counts()
         .values()
         .filter(|count| count == &&people)
         .count()
 }
 
-pub fn part_1(input: &str) -> usize {
-    input.trim().split("\n\n").map(|s| count_group_any(s)).sum()
+/// One bit per answered question, `a` being the lowest bit.
+fn answer_mask(person: &str) -> u32 {
+    person
+        .bytes()
+        .filter(u8::is_ascii_lowercase)
+        .fold(0, |mask, b| mask | 1 << (b - b'a'))
 }
 
-pub fn part_2(input: &str) -> usize {
-    input.trim().split("\n\n").map(|s| count_group_all(s)).sum()
+/// Same as `count_group_any`, but ORs a bitmask per person, so only answers
+/// `a` to `z` count.
+pub fn count_group_any_bits(group: &str) -> usize {
+    group
+        .lines()
+        .fold(0, |acc, person| acc | answer_mask(person))
+        .count_ones() as usize
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
+/// Same as `count_group_all`, but ANDs a bitmask per person, so only answers
+/// `a` to `z` count.
+pub fn count_group_all_bits(group: &str) -> usize {
+    group
+        .lines()
+        .map(answer_mask)
+        .reduce(|acc, mask| acc & mask)
+        .unwrap_or(0)
+        .count_ones() as usize
+}
 
-#[cfg(test)]
-mod part_1 {
-    use super::*;
+pub fn part_1(input: &str) -> usize {
+    input::blocks(input).map(count_group_any_bits).sum()
+}
 
-    #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 11);
-    }
+pub fn part_2(input: &str) -> usize {
+    input::blocks(input).map(count_group_all_bits).sum()
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 6259);
-    }
+pub fn part_1_hashed(input: &str) -> usize {
+    input::blocks(input).map(count_group_any).sum()
 }
 
-#[cfg(test)]
-mod part_2 {
-    use super::*;
+pub fn part_2_hashed(input: &str) -> usize {
+    input::blocks(input).map(count_group_all).sum()
+}
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 6);
-    }
+/// `size` groups of people answering random questions, `a` to `z`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let groups = (0..size)
+        .map(|_| {
+            (0..rng.between(1, 5))
+                .map(|_| {
+                    let mut person = (b'a'..=b'z')
+                        .filter(|_| rng.chance(1, 3))
+                        .map(char::from)
+                        .collect::<String>();
+                    if person.is_empty() {
+                        person.push('q');
+                    }
+                    person + "\n"
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    groups.join("\n")
+}
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 3178);
-    }
+aoc_day! {
+    year: 2020,
+    day: 6,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 11, answer: 6259, alternatives: [part_1_hashed] },
+    part_2: { example: 6, answer: 3178, alternatives: [part_2_hashed] },
 }
 
 #[cfg(test)]
@@ -87,4 +135,72 @@ mod tests {
         assert_eq!(count_group_all("a\na\na\na"), 1);
         assert_eq!(count_group_all("b"), 1);
     }
+
+    #[test]
+    fn test_hashed_any_alphabet() {
+        assert_eq!(count_group_any("äöü"), 3);
+        assert_eq!(count_group_any("äö\r\nöü"), 3);
+        assert_eq!(count_group_all("äö\r\nöü"), 1);
+        let letters = ('a'..='z').chain('A'..='Z').collect::<String>();
+        assert_eq!(count_group_any(&letters), 52);
+        assert_eq!(count_group_all(&format!("{}\n{}", letters, letters)), 52);
+        // The bitsets only know `a` to `z`.
+        assert_eq!(count_group_any_bits("äöü"), 0);
+    }
+
+    #[test]
+    fn test_count_group_any_bits() {
+        assert_eq!(count_group_any_bits(""), 0);
+        assert_eq!(count_group_any_bits("abc"), 3);
+        assert_eq!(count_group_any_bits("a\nb\nc"), 3);
+        assert_eq!(count_group_any_bits("ab\nac"), 3);
+        assert_eq!(count_group_any_bits("a\na\na\na"), 1);
+        assert_eq!(count_group_any_bits("b"), 1);
+        assert_eq!(count_group_any_bits("abcdefghijklmnopqrstuvwxyz"), 26);
+    }
+
+    #[test]
+    fn test_count_group_all_bits() {
+        assert_eq!(count_group_all_bits(""), 0);
+        assert_eq!(count_group_all_bits("abc"), 3);
+        assert_eq!(count_group_all_bits("a\nb\nc"), 0);
+        assert_eq!(count_group_all_bits("ab\nac"), 1);
+        assert_eq!(count_group_all_bits("a\na\na\na"), 1);
+        assert_eq!(count_group_all_bits("b"), 1);
+        assert_eq!(count_group_all_bits("abcdefghijklmnopqrstuvwxyz"), 26);
+    }
+
+    #[test]
+    fn test_bits_agree_with_hashed() {
+        for group in input::blocks(INPUT) {
+            assert_eq!(count_group_any_bits(group), count_group_any(group));
+            assert_eq!(count_group_all_bits(group), count_group_all(group));
+        }
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn everyone_is_at_most_anyone(people in prop::collection::vec("[a-z]{1,26}", 1..8)) {
+            let group = people.join("\n");
+            let any = count_group_any(&group);
+            let all = count_group_all(&group);
+            prop_assert!(all <= any && any <= 26);
+            prop_assert!(people.iter().all(|p| all <= p.len()));
+            prop_assert_eq!(count_group_any_bits(&group), any);
+            prop_assert_eq!(count_group_all_bits(&group), all);
+        }
+
+        #[test]
+        fn one_person_answers_alone(person in "[a-z]{1,26}") {
+            let distinct = person.chars().collect::<std::collections::HashSet<_>>().len();
+            prop_assert_eq!(count_group_any(&person), distinct);
+            prop_assert_eq!(count_group_all(&person), distinct);
+        }
+    }
 }
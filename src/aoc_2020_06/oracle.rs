@@ -0,0 +1,37 @@
+// Asks every group about every question from `a` to `z`.
+
+fn groups(input: &str) -> Vec<Vec<&str>> {
+    let mut groups = vec![Vec::new()];
+    for line in input.lines() {
+        if line.is_empty() {
+            groups.push(Vec::new());
+        } else {
+            groups.last_mut().unwrap().push(line);
+        }
+    }
+    groups
+}
+
+pub fn part_1(input: &str) -> usize {
+    let mut count = 0;
+    for group in groups(input) {
+        for question in 'a'..='z' {
+            if group.iter().any(|person| person.contains(question)) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+pub fn part_2(input: &str) -> usize {
+    let mut count = 0;
+    for group in groups(input) {
+        for question in 'a'..='z' {
+            if !group.is_empty() && group.iter().all(|person| person.contains(question)) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
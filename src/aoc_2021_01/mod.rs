@@ -1,56 +1,123 @@
-extern crate itertools;
+use crate::input;
+use crate::parse::ParseError;
+use crate::registry::aoc_day;
+use crate::rng::Rng;
+use crate::window::{self, ChangeCounter};
+use std::io::{self, BufRead};
 
-use itertools::Itertools;
+mod oracle;
 
-pub fn part_1(input: &str) -> usize {
-    input
-        .lines()
-        .map(|l| l.trim().parse::<i64>().unwrap())
-        .tuple_windows::<(_, _)>()
-        .filter(|(a, b)| a < b)
-        .count()
+/// Counts depth increases between `size`-wide windows read line by line from
+/// `reader`, using constant memory regardless of the input length.
+pub fn count_increases_from_reader<R: BufRead>(mut reader: R, size: usize) -> io::Result<usize> {
+    let mut counter = ChangeCounter::new(size);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let trimmed = input::strip_bom(&line).trim();
+        if !trimmed.is_empty() {
+            let depth = trimmed
+                .parse::<i64>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            counter.push(depth);
+        }
+        line.clear();
+    }
+    Ok(counter.increases())
+}
+
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    input::numbers(input)
+}
+
+pub fn part_1(depths: &[i64]) -> usize {
+    window::count_increases(depths, 1)
+}
+
+pub fn part_2(depths: &[i64]) -> usize {
+    window::count_increases(depths, 3)
 }
 
-pub fn part_2(input: &str) -> usize {
-    input
-        .lines()
-        .map(|l| l.trim().parse::<i64>().unwrap())
-        .tuple_windows::<(_, _, _)>()
-        .map(|(a, b, c)| a + b + c)
-        .tuple_windows::<(_, _)>()
-        .filter(|(a, b)| a < b)
-        .count()
+/// `size` sonar depths that mostly go deeper, a little at a time, like the
+/// sea floor in the real ones.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.between(100, 200) as i64;
+    (0..size)
+        .map(|_| {
+            depth = (depth + rng.between(0, 30) as i64 - 10).max(0);
+            format!("{}\n", depth)
+        })
+        .collect()
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
+aoc_day! {
+    year: 2021,
+    day: 1,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 7, answer: 1624 },
+    part_2: { example: 5, answer: 1653 },
+}
 
 #[cfg(test)]
-mod part_1 {
+mod tests {
     use super::*;
+    use std::io::Cursor;
 
     #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 7);
-    }
-
-    #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 1624);
+    fn test_count_increases_from_reader() {
+        assert_eq!(
+            count_increases_from_reader(Cursor::new(EXAMPLE), 1).unwrap(),
+            7
+        );
+        assert_eq!(
+            count_increases_from_reader(Cursor::new(EXAMPLE), 3).unwrap(),
+            5
+        );
+        assert_eq!(
+            count_increases_from_reader(Cursor::new(INPUT), 3).unwrap(),
+            1653
+        );
+        assert_eq!(
+            count_increases_from_reader(Cursor::new("1\n\n2\n"), 1).unwrap(),
+            1
+        );
+        assert!(count_increases_from_reader(Cursor::new("1\nx\n"), 1).is_err());
     }
 }
 
 #[cfg(test)]
-mod part_2 {
+mod properties {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 5);
-    }
+    proptest! {
+        #[test]
+        fn changes_are_bounded_by_window_count(
+            depths in prop::collection::vec(-1000i64..1000, 0..100),
+            size in 1usize..5,
+        ) {
+            let comparisons = depths.len().saturating_sub(size);
+            let increases = window::count_increases(&depths, size);
+            let decreases = window::count_decreases(&depths, size);
+            prop_assert!(increases + decreases <= comparisons);
+        }
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 1653);
+        #[test]
+        fn depths_round_trip(depths in prop::collection::vec(0i64..10000, 0..100)) {
+            let input = depths.iter().map(|d| format!("{}\n", d)).collect::<String>();
+            prop_assert_eq!(parse(&input), Ok(depths.clone()));
+            prop_assert_eq!(
+                count_increases_from_reader(input.as_bytes(), 3).unwrap(),
+                part_2(&depths)
+            );
+        }
+
+        #[test]
+        fn sorted_depths_only_increase(mut depths in prop::collection::vec(0i64..10000, 0..100)) {
+            depths.sort();
+            depths.dedup();
+            prop_assert_eq!(part_1(&depths), depths.len().saturating_sub(1));
+        }
     }
 }
@@ -0,0 +1,28 @@
+// Sums every three-measurement window in full instead of sliding it.
+
+fn depths(input: &str) -> Vec<i64> {
+    input.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+fn increases(sums: &[i64]) -> usize {
+    let mut count = 0;
+    for i in 1..sums.len() {
+        if sums[i] > sums[i - 1] {
+            count += 1;
+        }
+    }
+    count
+}
+
+pub fn part_1(input: &str) -> usize {
+    increases(&depths(input))
+}
+
+pub fn part_2(input: &str) -> usize {
+    let depths = depths(input);
+    let mut sums = Vec::new();
+    for i in 2..depths.len() {
+        sums.push(depths[i - 2] + depths[i - 1] + depths[i]);
+    }
+    increases(&sums)
+}
@@ -0,0 +1,202 @@
+use super::Position;
+use std::fmt::Write;
+
+/// One course to draw, e.g. the trajectory of a `Submarine`.
+#[derive(Debug, Clone, Copy)]
+pub struct Series<'a> {
+    pub name: &'a str,
+    pub color: &'a str,
+    pub trajectory: &'a [Position],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    pub width: f64,
+    pub height: f64,
+    pub margin: f64,
+    /// Draw a marker on every `marker_every`th position, or none if 0.
+    pub marker_every: usize,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            width: 800.0,
+            height: 400.0,
+            margin: 40.0,
+            marker_every: 0,
+        }
+    }
+}
+
+/// One row per position: `step,horizontal,depth,aim`.
+pub fn to_csv(trajectory: &[Position]) -> String {
+    let mut csv = String::from("step,horizontal,depth,aim\n");
+    for (step, p) in trajectory.iter().enumerate() {
+        writeln!(csv, "{},{},{},{}", step, p.horizontal, p.depth, p.aim).unwrap();
+    }
+    csv
+}
+
+/// Maps values in `lo..=hi` onto `from..=to`.
+struct Axis {
+    lo: f64,
+    hi: f64,
+    from: f64,
+    to: f64,
+}
+
+impl Axis {
+    fn new(values: impl Iterator<Item = i32>, from: f64, to: f64) -> Axis {
+        let (lo, hi) = values.fold((0, 0), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        Axis {
+            lo: lo as f64,
+            hi: hi as f64,
+            from,
+            to,
+        }
+    }
+
+    fn scale(&self, v: i32) -> f64 {
+        if self.hi == self.lo {
+            return self.from;
+        }
+        self.from + (v as f64 - self.lo) / (self.hi - self.lo) * (self.to - self.from)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draws every series as a polyline on shared axes, horizontal position to
+/// the right and depth downward. The origin is always included.
+pub fn to_svg(series: &[Series], options: &SvgOptions) -> String {
+    let SvgOptions {
+        width,
+        height,
+        margin,
+        marker_every,
+    } = *options;
+    let points = || series.iter().flat_map(|s| s.trajectory.iter());
+    let x = Axis::new(points().map(|p| p.horizontal), margin, width - margin);
+    let y = Axis::new(points().map(|p| p.depth), margin, height - margin);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<g stroke="black" font-size="10" font-family="sans-serif"><line x1="{x0}" y1="{y0}" x2="{x1}" y2="{y0}"/><line x1="{x0}" y1="{y0}" x2="{x0}" y2="{y1}"/><text x="{x1}" y="{ty}" text-anchor="end" stroke="none">horizontal {hx}</text><text x="{tx}" y="{y1}" text-anchor="end" stroke="none">depth {hy}</text></g>"#,
+        x0 = x.from,
+        x1 = x.to,
+        y0 = y.from,
+        y1 = y.to,
+        tx = x.from - 4.0,
+        ty = y.from - 4.0,
+        hx = x.hi,
+        hy = y.hi,
+    )
+    .unwrap();
+    for (i, s) in series.iter().enumerate() {
+        let color = escape(s.color);
+        let coords: Vec<String> = s
+            .trajectory
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", x.scale(p.horizontal), y.scale(p.depth)))
+            .collect();
+        writeln!(
+            svg,
+            r#"<polyline fill="none" stroke="{}" points="{}"><title>{}</title></polyline>"#,
+            color,
+            coords.join(" "),
+            escape(s.name)
+        )
+        .unwrap();
+        if marker_every > 0 {
+            for (step, p) in s.trajectory.iter().enumerate().step_by(marker_every) {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="2" fill="{}"><title>{} step {}</title></circle>"#,
+                    x.scale(p.horizontal),
+                    y.scale(p.depth),
+                    color,
+                    escape(s.name),
+                    step
+                )
+                .unwrap();
+            }
+        }
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}" text-anchor="end" font-size="12" font-family="sans-serif">{}</text>"#,
+            width - margin,
+            height - margin + 16.0 * (i + 1) as f64 - 4.0,
+            color,
+            escape(s.name)
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(horizontal: i32, depth: i32, aim: i32) -> Position {
+        Position {
+            horizontal,
+            depth,
+            aim,
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            to_csv(&[pos(0, 0, 0), pos(5, 0, 0), pos(5, 0, 5)]),
+            "step,horizontal,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,0,5\n"
+        );
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let trajectory = [pos(0, 0, 0), pos(10, 0, 0), pos(10, 20, 0), pos(20, 40, 0)];
+        let options = SvgOptions {
+            width: 120.0,
+            height: 60.0,
+            margin: 10.0,
+            marker_every: 2,
+        };
+        let svg = to_svg(
+            &[Series {
+                name: "a<b",
+                color: "red",
+                trajectory: &trajectory,
+            }],
+            &options,
+        );
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"points="10.00,10.00 60.00,10.00 60.00,30.00 110.00,50.00""#));
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains("a&lt;b step 2"));
+    }
+
+    #[test]
+    fn test_to_svg_empty() {
+        let svg = to_svg(&[], &SvgOptions::default());
+        assert!(svg.starts_with("<svg "));
+        assert!(!svg.contains("<polyline"));
+    }
+}
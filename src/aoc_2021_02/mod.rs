@@ -1,113 +1,405 @@
-extern crate itertools;
+use crate::overflow::Overflow;
+use crate::registry::aoc_day;
+use crate::rng::Rng;
+use crate::AocParse;
 
-use serde::Deserialize;
-use serde_scan;
+mod export;
+mod oracle;
+mod planner;
+mod program;
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all(deserialize = "lowercase"))]
-enum Direction {
+pub use crate::parse::ParseError;
+pub use export::{to_csv, to_svg, Series, SvgOptions};
+pub use planner::{plan, verify};
+pub use program::{Commands, Program, Statement};
+
+// https://adventofcode.com/2021/day/2
+
+/// A single command, parsed from a line such as `forward 5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AocParse)]
+pub enum Direction {
     Forward(i32),
     Down(i32),
     Up(i32),
+    Back(i32),
 }
 
-struct Position {
-    horizontal: i32,
-    depth: i32,
-    aim: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub horizontal: i32,
+    pub depth: i32,
+    pub aim: i32,
 }
 
-pub fn part_1(input: &str) -> i32 {
-    let position = input
-        .lines()
-        .map(|l| serde_scan::from_str::<Direction>(l).unwrap())
-        .fold(
-            Position {
-                horizontal: 0,
-                depth: 0,
-                aim: 0,
+/// How a submarine interprets a single command.
+///
+/// Returns `None` if the command would overflow the position.
+pub trait CommandSemantics {
+    fn apply(&self, pos: Position, dir: Direction) -> Option<Position>;
+}
+
+/// Part 1: `down` and `up` change the depth directly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Direct;
+
+impl CommandSemantics for Direct {
+    fn apply(&self, pos: Position, dir: Direction) -> Option<Position> {
+        Some(match dir {
+            Direction::Forward(n) => Position {
+                horizontal: pos.horizontal.checked_add(n)?,
+                ..pos
             },
-            |pos, dir| match dir {
-                Direction::Forward(n) => Position {
-                    horizontal: pos.horizontal + n,
-                    depth: pos.depth,
-                    aim: 0,
-                },
-                Direction::Down(n) => Position {
-                    horizontal: pos.horizontal,
-                    depth: pos.depth + n,
-                    aim: 0,
-                },
-                Direction::Up(n) => Position {
-                    horizontal: pos.horizontal,
-                    depth: pos.depth - n,
-                    aim: 0,
-                },
+            Direction::Back(n) => Position {
+                horizontal: pos.horizontal.checked_sub(n)?,
+                ..pos
             },
-        );
-    position.horizontal * position.depth
+            Direction::Down(n) => Position {
+                depth: pos.depth.checked_add(n)?,
+                ..pos
+            },
+            Direction::Up(n) => Position {
+                depth: pos.depth.checked_sub(n)?,
+                ..pos
+            },
+        })
+    }
 }
 
-pub fn part_2(input: &str) -> i32 {
-    let position = input
-        .lines()
-        .map(|l| serde_scan::from_str::<Direction>(l).unwrap())
-        .fold(
-            Position {
-                horizontal: 0,
-                depth: 0,
-                aim: 0,
+/// Part 2: `down` and `up` change the aim and `forward` dives along it.
+/// `back` reverses along the current aim.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Aimed;
+
+impl CommandSemantics for Aimed {
+    fn apply(&self, pos: Position, dir: Direction) -> Option<Position> {
+        Some(match dir {
+            Direction::Forward(n) => Position {
+                horizontal: pos.horizontal.checked_add(n)?,
+                depth: pos.depth.checked_add(n.checked_mul(pos.aim)?)?,
+                ..pos
             },
-            |pos, dir| match dir {
-                Direction::Forward(n) => Position {
-                    horizontal: pos.horizontal + n,
-                    depth: pos.depth + (n * pos.aim),
-                    aim: pos.aim,
-                },
-                Direction::Down(n) => Position {
-                    horizontal: pos.horizontal,
-                    depth: pos.depth,
-                    aim: pos.aim + n,
-                },
-                Direction::Up(n) => Position {
-                    horizontal: pos.horizontal,
-                    depth: pos.depth,
-                    aim: pos.aim - n,
-                },
+            Direction::Back(n) => Position {
+                horizontal: pos.horizontal.checked_sub(n)?,
+                depth: pos.depth.checked_sub(n.checked_mul(pos.aim)?)?,
+                ..pos
             },
-        );
-    position.horizontal * position.depth
+            Direction::Down(n) => Position {
+                aim: pos.aim.checked_add(n)?,
+                ..pos
+            },
+            Direction::Up(n) => Position {
+                aim: pos.aim.checked_sub(n)?,
+                ..pos
+            },
+        })
+    }
+}
+
+/// A command that would have moved the submarine out of `i32` range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandOverflow {
+    /// Zero-based index of the offending command.
+    pub step: usize,
+    pub command: Direction,
 }
 
-pub static EXAMPLE: &str = std::include_str!("example.txt");
-pub static INPUT: &str = std::include_str!("input.txt");
+impl std::fmt::Display for CommandOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "command {} ({:?}) overflows",
+            self.step + 1,
+            self.command
+        )
+    }
+}
+
+impl std::error::Error for CommandOverflow {}
+
+impl From<CommandOverflow> for Overflow {
+    fn from(_: CommandOverflow) -> Self {
+        Overflow
+    }
+}
+
+/// A submarine that executes commands one at a time and remembers every
+/// position it has been in, starting with the origin.
+#[derive(Debug, Clone)]
+pub struct Submarine<S> {
+    semantics: S,
+    trajectory: Vec<Position>,
+}
+
+impl<S: CommandSemantics> Submarine<S> {
+    pub fn new(semantics: S) -> Self {
+        Submarine {
+            semantics,
+            trajectory: vec![Position::default()],
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        *self.trajectory.last().unwrap()
+    }
+
+    pub fn trajectory(&self) -> &[Position] {
+        &self.trajectory
+    }
+
+    pub fn into_trajectory(self) -> Vec<Position> {
+        self.trajectory
+    }
+
+    /// Executes a single command. On overflow the submarine stays where it was.
+    pub fn step(&mut self, dir: Direction) -> Result<Position, CommandOverflow> {
+        let next = self
+            .semantics
+            .apply(self.position(), dir)
+            .ok_or(CommandOverflow {
+                step: self.trajectory.len() - 1,
+                command: dir,
+            })?;
+        self.trajectory.push(next);
+        Ok(next)
+    }
+
+    pub fn run(
+        &mut self,
+        dirs: impl IntoIterator<Item = Direction>,
+    ) -> Result<Position, CommandOverflow> {
+        for dir in dirs {
+            self.step(dir)?;
+        }
+        Ok(self.position())
+    }
+}
+
+pub fn parse(input: &str) -> Result<Program, ParseError> {
+    input.parse()
+}
+
+/// The product is widened to `i64`, which can't overflow for two `i32`s.
+pub fn part_1(program: &Program) -> Result<i64, Overflow> {
+    let position = Submarine::new(Direct).run(program.commands())?;
+    Ok(position.horizontal as i64 * position.depth as i64)
+}
+
+/// The product is widened to `i64`, which can't overflow for two `i32`s.
+pub fn part_2(program: &Program) -> Result<i64, Overflow> {
+    let position = Submarine::new(Aimed).run(program.commands())?;
+    Ok(position.horizontal as i64 * position.depth as i64)
+}
+
+/// A program that runs `size` commands, some of them in small repeat
+/// blocks. `up` never takes the aim above the surface, `back` never takes the
+/// submarine behind its start, and the aim is kept below a few hundred so
+/// that even millions of commands stay within `i32`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut program = String::new();
+    let (mut horizontal, mut aim) = (0, 0);
+    let mut remaining = size as u64;
+    while remaining > 0 {
+        if remaining >= 4 && rng.chance(1, 10) {
+            let count = rng.between(2, 4.min(remaining / 2));
+            let len = rng.between(1, (remaining / count).min(3));
+            program.push_str(&format!("repeat {} {{\n", count));
+            for _ in 0..len {
+                let n = rng.between(1, 9);
+                let command = if rng.chance(1, 2) {
+                    horizontal += count * n;
+                    "forward"
+                } else if aim >= count * n {
+                    aim -= count * n;
+                    "up"
+                } else {
+                    aim += count * n;
+                    "down"
+                };
+                program.push_str(&format!("    {} {}\n", command, n));
+            }
+            program.push_str("}\n");
+            remaining -= count * len;
+            continue;
+        }
+        let n = rng.between(1, 9);
+        let command = match rng.below(10) {
+            0 if horizontal >= n => {
+                horizontal -= n;
+                "back"
+            }
+            _ if aim > 200 => {
+                aim -= n;
+                "up"
+            }
+            1..=3 if aim >= n => {
+                aim -= n;
+                "up"
+            }
+            0..=5 => {
+                aim += n;
+                "down"
+            }
+            _ => {
+                horizontal += n;
+                "forward"
+            }
+        };
+        program.push_str(&format!("{} {}\n", command, n));
+        remaining -= 1;
+    }
+    program
+}
+
+aoc_day! {
+    year: 2021,
+    day: 2,
+    parse: parse,
+    generate: generate,
+    oracle: oracle,
+    part_1: { example: 150, answer: 1815044 },
+    part_2: { example: 900, answer: 1739283308 },
+}
 
 #[cfg(test)]
-mod part_1 {
+mod tests {
     use super::*;
 
     #[test]
-    fn example() {
-        assert_eq!(part_1(EXAMPLE), 150);
+    fn test_step() {
+        let mut sub = Submarine::new(Aimed);
+        assert_eq!(
+            sub.step(Direction::Forward(5)).unwrap(),
+            Position {
+                horizontal: 5,
+                depth: 0,
+                aim: 0
+            }
+        );
+        assert_eq!(
+            sub.step(Direction::Down(5)).unwrap(),
+            Position {
+                horizontal: 5,
+                depth: 0,
+                aim: 5
+            }
+        );
+        assert_eq!(
+            sub.step(Direction::Forward(8)).unwrap(),
+            Position {
+                horizontal: 13,
+                depth: 40,
+                aim: 5
+            }
+        );
     }
 
     #[test]
-    fn answer() {
-        assert_eq!(part_1(INPUT), 1815044);
+    fn test_trajectory() {
+        let mut sub = Submarine::new(Direct);
+        let program: Program = EXAMPLE.parse().unwrap();
+        sub.run(program.commands()).unwrap();
+        let trajectory = sub.trajectory();
+        assert_eq!(trajectory.len(), EXAMPLE.lines().count() + 1);
+        assert_eq!(trajectory[0], Position::default());
+        assert!(trajectory.iter().all(|p| p.aim == 0));
+        assert_eq!(
+            trajectory.last(),
+            Some(&Position {
+                horizontal: 15,
+                depth: 10,
+                aim: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!("forward 5".parse(), Ok(Direction::Forward(5)));
+        assert_eq!("back 3".parse(), Ok(Direction::Back(3)));
+        assert!("sideways 3".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_back() {
+        let mut sub = Submarine::new(Aimed);
+        let program: Program = "down 2\nforward 5\nback 3".parse().unwrap();
+        assert_eq!(
+            sub.run(program.commands()),
+            Ok(Position {
+                horizontal: 2,
+                depth: 4,
+                aim: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut sub = Submarine::new(Aimed);
+        let program: Program = "down 100000\nforward 100000".parse().unwrap();
+        assert_eq!(
+            sub.run(program.commands()),
+            Err(CommandOverflow {
+                step: 1,
+                command: Direction::Forward(100000)
+            })
+        );
+        assert_eq!(sub.trajectory().len(), 2);
+
+        let mut sub = Submarine::new(Direct);
+        let program: Program = "repeat 3 { down 1000000000 }".parse().unwrap();
+        assert!(sub.run(program.commands()).is_err());
+        assert_eq!(sub.position().depth, 2000000000);
     }
 }
 
 #[cfg(test)]
-mod part_2 {
+mod properties {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn example() {
-        assert_eq!(part_2(EXAMPLE), 900);
+    fn direction() -> impl Strategy<Value = Direction> {
+        prop_oneof![
+            (0i32..100).prop_map(Direction::Forward),
+            (0i32..100).prop_map(Direction::Down),
+            (0i32..100).prop_map(Direction::Up),
+            (0i32..100).prop_map(Direction::Back),
+        ]
     }
 
-    #[test]
-    fn answer() {
-        assert_eq!(part_2(INPUT), 1739283308);
+    fn line(dir: &Direction) -> String {
+        match dir {
+            Direction::Forward(n) => format!("forward {}\n", n),
+            Direction::Down(n) => format!("down {}\n", n),
+            Direction::Up(n) => format!("up {}\n", n),
+            Direction::Back(n) => format!("back {}\n", n),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn programs_round_trip(commands in prop::collection::vec(direction(), 0..50)) {
+            let text = commands.iter().map(line).collect::<String>();
+            let program = parse(&text).unwrap();
+            prop_assert_eq!(program.commands().collect::<Vec<_>>(), commands);
+        }
+
+        #[test]
+        fn repeats_expand_their_body(
+            count in 0u32..5,
+            body in prop::collection::vec(direction(), 0..10),
+        ) {
+            let text = format!("repeat {} {{\n{}}}\n", count, body.iter().map(line).collect::<String>());
+            let expanded = parse(&text).unwrap().commands().collect::<Vec<_>>();
+            prop_assert_eq!(expanded, body.repeat(count as usize));
+        }
+
+        #[test]
+        fn aim_follows_direct_depth(commands in prop::collection::vec(direction(), 0..50)) {
+            let direct = Submarine::new(Direct).run(commands.iter().copied()).unwrap();
+            let aimed = Submarine::new(Aimed).run(commands.iter().copied()).unwrap();
+            prop_assert_eq!(direct.horizontal, aimed.horizontal);
+            prop_assert_eq!(direct.depth, aimed.aim);
+        }
     }
 }
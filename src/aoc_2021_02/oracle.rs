@@ -0,0 +1,67 @@
+// Writes repeat blocks out in full and follows the commands one by one, with
+// `i64` positions so nothing overflows on generated inputs.
+
+/// The commands of the program, with repeat blocks written out, starting at
+/// token `*next` and up to the end of the enclosing block.
+fn expand(tokens: &[&str], next: &mut usize) -> Vec<(String, i64)> {
+    let mut commands = Vec::new();
+    while *next < tokens.len() && tokens[*next] != "}" {
+        let word = tokens[*next];
+        let n = tokens[*next + 1].parse::<i64>().unwrap();
+        *next += 2;
+        if word == "repeat" {
+            *next += 1; // `{`
+            let body = expand(tokens, next);
+            *next += 1; // `}`
+            for _ in 0..n {
+                commands.extend(body.iter().cloned());
+            }
+        } else {
+            commands.push((word.to_string(), n));
+        }
+    }
+    commands
+}
+
+fn commands(input: &str) -> Vec<(String, i64)> {
+    let mut tokens = Vec::new();
+    for line in input.lines() {
+        let code = line.split('#').next().unwrap();
+        tokens.extend(code.split_whitespace());
+    }
+    expand(&tokens, &mut 0)
+}
+
+pub fn part_1(input: &str) -> i64 {
+    let (mut horizontal, mut depth) = (0, 0);
+    for (command, n) in commands(input) {
+        match command.as_str() {
+            "forward" => horizontal += n,
+            "back" => horizontal -= n,
+            "down" => depth += n,
+            "up" => depth -= n,
+            _ => panic!("unknown command `{}`", command),
+        }
+    }
+    horizontal * depth
+}
+
+pub fn part_2(input: &str) -> i64 {
+    let (mut horizontal, mut depth, mut aim) = (0, 0, 0);
+    for (command, n) in commands(input) {
+        match command.as_str() {
+            "forward" => {
+                horizontal += n;
+                depth += aim * n;
+            }
+            "back" => {
+                horizontal -= n;
+                depth -= aim * n;
+            }
+            "down" => aim += n,
+            "up" => aim -= n,
+            _ => panic!("unknown command `{}`", command),
+        }
+    }
+    horizontal * depth
+}
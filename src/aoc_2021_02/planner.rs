@@ -0,0 +1,192 @@
+use super::{Aimed, Direction, Submarine};
+
+/// Changes the aim by `delta` with a single `down` or `up`, if needed. An
+/// aim of `i32::MIN` takes two `up`s, as its negation doesn't fit in `i32`.
+fn aim_by(delta: i32) -> Vec<Direction> {
+    match delta {
+        0 => vec![],
+        i32::MIN => vec![Direction::Up(i32::MAX), Direction::Up(1)],
+        d if d > 0 => vec![Direction::Down(d)],
+        d => vec![Direction::Up(-d)],
+    }
+}
+
+/// Moves `distance` along the current aim with a single `forward` or `back`,
+/// if needed. Going back `i32::MIN` takes two `back`s.
+fn move_by(distance: i32) -> Vec<Direction> {
+    match distance {
+        0 => vec![],
+        i32::MIN => vec![Direction::Back(i32::MAX), Direction::Back(1)],
+        d if d > 0 => vec![Direction::Forward(d)],
+        d => vec![Direction::Back(-d)],
+    }
+}
+
+/// Largest divisor of `n` that is below `limit`.
+fn largest_divisor_below(n: i32, limit: u32) -> Option<i32> {
+    let n = n.unsigned_abs() as u64;
+    let limit = limit as u64;
+    (1..)
+        .take_while(|i| i * i <= n)
+        .filter(|&i| n.is_multiple_of(i))
+        .flat_map(|i| [i, n / i])
+        .filter(|&d| d < limit)
+        .max()
+        .map(|d| d as i32)
+}
+
+fn candidate(horizontal: i32, depth: i32) -> Option<Vec<Direction>> {
+    if horizontal == 0 {
+        if depth == 0 {
+            return Some(vec![]);
+        }
+        // Go back one level, then aim so that coming forward one again
+        // reaches the depth.
+        let mut commands = vec![Direction::Back(1)];
+        commands.extend(aim_by(depth));
+        commands.push(Direction::Forward(1));
+        return Some(commands);
+    }
+    if depth.checked_rem(horizontal) == Some(0) {
+        // Aim once and go all the way.
+        let mut commands = aim_by(depth / horizontal);
+        commands.extend(move_by(horizontal));
+        return Some(commands);
+    }
+    // Go part of the way level, then aim so that the rest reaches the depth.
+    let rest = largest_divisor_below(depth, horizontal.unsigned_abs())? * horizontal.signum();
+    let mut commands = move_by(horizontal - rest);
+    commands.extend(aim_by(depth.checked_div(rest)?));
+    commands.extend(move_by(rest));
+    Some(commands)
+}
+
+/// Checks that `commands` take a submarine with `Aimed` semantics from the
+/// origin to exactly `(horizontal, depth)`.
+pub fn verify(commands: &[Direction], horizontal: i32, depth: i32) -> bool {
+    match Submarine::new(Aimed).run(commands.iter().copied()) {
+        Ok(p) => p.horizontal == horizontal && p.depth == depth,
+        Err(_) => false,
+    }
+}
+
+/// Builds a course of `forward`, `back`, `down` and `up` commands that takes
+/// a submarine with `Aimed` semantics from the origin to exactly
+/// `(horizontal, depth)`, and checks it with [`verify`].
+///
+/// The course isn't searched for. It goes part of the way level, aims once
+/// and goes the rest of the way, forward or back depending on the sign of
+/// `horizontal`; to end up where it started horizontally, it goes back one,
+/// aims and comes forward one again. That's at most three commands, or four
+/// when an amount of `i32::MIN` has to be split in two.
+///
+/// Returns `None` if the course takes more than `max_steps` commands, and
+/// for `(-1, i32::MIN)`, which would need an aim of 2^31.
+pub fn plan(horizontal: i32, depth: i32, max_steps: usize) -> Option<Vec<Direction>> {
+    candidate(horizontal, depth)
+        .filter(|commands| commands.len() <= max_steps)
+        .filter(|commands| verify(commands, horizontal, depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(plan(0, 0, 0), Some(vec![]));
+        assert_eq!(plan(15, 0, 5), Some(vec![Direction::Forward(15)]));
+        assert_eq!(
+            plan(15, 60, 5),
+            Some(vec![Direction::Down(4), Direction::Forward(15)])
+        );
+        assert_eq!(
+            plan(15, -30, 5),
+            Some(vec![Direction::Up(2), Direction::Forward(15)])
+        );
+        assert_eq!(
+            plan(10, 21, 5),
+            Some(vec![
+                Direction::Forward(3),
+                Direction::Down(3),
+                Direction::Forward(7)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plan_back() {
+        assert_eq!(plan(-5, 0, 10), Some(vec![Direction::Back(5)]));
+        assert_eq!(
+            plan(-10, 21, 5),
+            Some(vec![
+                Direction::Back(3),
+                Direction::Up(3),
+                Direction::Back(7)
+            ])
+        );
+        assert_eq!(
+            plan(0, 5, 10),
+            Some(vec![
+                Direction::Back(1),
+                Direction::Down(5),
+                Direction::Forward(1)
+            ])
+        );
+        assert_eq!(
+            plan(0, -5, 10),
+            Some(vec![
+                Direction::Back(1),
+                Direction::Up(5),
+                Direction::Forward(1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plan_over_budget() {
+        assert_eq!(plan(10, 21, 2), None);
+        assert_eq!(plan(15, 60, 1), None);
+        assert_eq!(plan(0, 5, 2), None);
+    }
+
+    #[test]
+    fn test_plan_exhaustive() {
+        for horizontal in -30..30 {
+            for depth in -100..100 {
+                let commands = plan(horizontal, depth, 3).unwrap();
+                assert!(verify(&commands, horizontal, depth));
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_extremes() {
+        for &(horizontal, depth) in &[
+            (i32::MAX, i32::MAX),
+            (i32::MAX, i32::MIN),
+            (i32::MIN, i32::MAX),
+            (i32::MIN, i32::MIN),
+            (i32::MIN, 0),
+            (0, i32::MIN),
+            (2, i32::MIN),
+            (1, i32::MIN + 1),
+            (-1, i32::MAX),
+            (i32::MAX, 1),
+        ] {
+            let commands = plan(horizontal, depth, 4).unwrap();
+            assert!(verify(&commands, horizontal, depth));
+        }
+        let commands = plan(1, i32::MIN, 3).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                Direction::Up(i32::MAX),
+                Direction::Up(1),
+                Direction::Forward(1)
+            ]
+        );
+        assert!(verify(&commands, 1, i32::MIN));
+        assert_eq!(plan(-1, i32::MIN, 10), None);
+    }
+}
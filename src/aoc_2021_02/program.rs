@@ -0,0 +1,365 @@
+use super::Direction;
+use crate::input;
+use crate::parse::{
+    many0, parse_all, spaces, tag, take_while1, Input, PResult, ParseError, Parser,
+};
+use std::str::FromStr;
+
+/// A parsed submarine program.
+///
+/// One command per line (`forward`, `down`, `up` or `back` followed by an
+/// amount, signed like `Direction` reads it), with `#` comments, blank lines and nestable repeat blocks:
+///
+/// ```text
+/// # dive in steps
+/// repeat 3 {
+///     down 2
+///     forward 5
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Command(Direction),
+    Repeat(u32, Vec<Statement>),
+}
+
+impl Program {
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+
+    /// How many commands the program expands to, or `None` if that doesn't
+    /// fit in a `u64`.
+    pub fn command_count(&self) -> Option<u64> {
+        fn count(statements: &[Statement]) -> Option<u64> {
+            statements.iter().try_fold(0u64, |total, statement| {
+                let n = match statement {
+                    Statement::Command(_) => 1,
+                    Statement::Repeat(times, body) => {
+                        count(body)?.checked_mul(u64::from(*times))?
+                    }
+                };
+                total.checked_add(n)
+            })
+        }
+        count(&self.statements)
+    }
+
+    /// The commands of the program with repeat blocks expanded lazily, so a
+    /// large repeat count doesn't need memory proportional to its expansion.
+    pub fn commands(&self) -> Commands<'_> {
+        Commands {
+            stack: vec![Frame {
+                body: &self.statements,
+                next: 0,
+                remaining: 0,
+            }],
+        }
+    }
+}
+
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let statements = parse_all(
+            input::strip_bom(s),
+            trivia.then(|input| block(input, 0)).map(|(_, b)| b),
+        )?;
+        Ok(Program { statements })
+    }
+}
+
+struct Frame<'a> {
+    body: &'a [Statement],
+    next: usize,
+    remaining: u32,
+}
+
+pub struct Commands<'a> {
+    stack: Vec<Frame<'a>>,
+}
+
+impl<'a> Iterator for Commands<'a> {
+    type Item = Direction;
+
+    fn next(&mut self) -> Option<Direction> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            match frame.body.get(frame.next) {
+                Some(Statement::Command(dir)) => {
+                    frame.next += 1;
+                    return Some(*dir);
+                }
+                Some(Statement::Repeat(count, body)) => {
+                    frame.next += 1;
+                    if *count > 0 && !body.is_empty() {
+                        self.stack.push(Frame {
+                            body,
+                            next: 0,
+                            remaining: count - 1,
+                        });
+                    }
+                }
+                None if frame.remaining > 0 => {
+                    frame.remaining -= 1;
+                    frame.next = 0;
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Whitespace, blank lines and `#` comments. Never fails, so it doesn't
+/// clutter errors with what else could have come next.
+fn trivia(mut input: Input) -> PResult<()> {
+    loop {
+        let rest = input.rest();
+        let skipped = rest.len() - rest.trim_start().len();
+        input = input.advance(skipped);
+        if !input.rest().starts_with('#') {
+            return Ok(((), input));
+        }
+        let comment = input.rest().find('\n').unwrap_or(input.rest().len());
+        input = input.advance(comment);
+    }
+}
+
+/// Digits, with a leading `-` or `+` if `signed`, the way `Direction` reads
+/// amounts.
+fn number<'a, T: FromStr>(what: &'static str, signed: bool) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let sign = usize::from(signed && rest.starts_with(['-', '+']));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return input.expected(what);
+        }
+        let n = &rest[..sign + digits];
+        match n.parse() {
+            Ok(value) => Ok((value, input.advance(n.len()))),
+            Err(_) => input.fail(format!("{} `{}` is out of range", what, n)),
+        }
+    }
+}
+
+/// How deeply repeat blocks may be nested, so hostile input can't overflow
+/// the stack.
+const MAX_DEPTH: usize = 64;
+
+fn statement<'a>(input: Input<'a>, depth: usize) -> PResult<'a, Statement> {
+    take_while1(|c| c.is_ascii_alphabetic(), "a command")
+        .try_map(|word| match word {
+            "forward" | "down" | "up" | "back" | "repeat" => Ok(word),
+            _ => Err(format!("unknown command `{}`", word)),
+        })
+        .skip(spaces())
+        .and_then(|word| {
+            move |input: Input<'a>| match word {
+                "repeat" if depth == MAX_DEPTH => input.fail("repeat blocks are nested too deeply"),
+                "repeat" => number("repeat count", false)
+                    .skip(spaces())
+                    .then(move |input| repeat_body(input, depth + 1))
+                    .map(|(count, body)| Statement::Repeat(count, body))
+                    .parse(input),
+                _ => number("amount", true)
+                    .map(|n| {
+                        Statement::Command(match word {
+                            "forward" => Direction::Forward(n),
+                            "down" => Direction::Down(n),
+                            "up" => Direction::Up(n),
+                            _ => Direction::Back(n),
+                        })
+                    })
+                    .parse(input),
+            }
+        })
+        .parse(input)
+}
+
+/// A `{`-delimited repeat body, nested `depth` blocks deep.
+fn repeat_body(input: Input, depth: usize) -> PResult<Vec<Statement>> {
+    let (_, rest) = tag("{").skip(trivia).parse(input)?;
+    let (body, rest) = block(rest, depth)?;
+    if rest.rest().is_empty() {
+        return input.abort("unclosed `{`");
+    }
+    let (_, rest) = tag("}").parse(rest)?;
+    Ok((body, rest))
+}
+
+/// The end of a statement: a line break, possibly after a comment, or the
+/// `}` or end of input that closes the block.
+fn line_end(input: Input) -> PResult<()> {
+    let (_, input) = spaces().parse(input)?;
+    let rest = input.rest();
+    if rest.is_empty() || rest.starts_with(['\n', '\r', '#', '}']) {
+        Ok(((), input))
+    } else {
+        input.expected("a line break")
+    }
+}
+
+/// Statements up to the end of the input or of the enclosing repeat block,
+/// which is nested `depth` blocks deep.
+fn block<'a>(input: Input<'a>, depth: usize) -> PResult<'a, Vec<Statement>> {
+    let statement = move |input: Input<'a>| statement(input, depth);
+    many0(statement.skip(line_end).skip(trivia)).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(s: &str) -> (usize, usize, String) {
+        let e = s.parse::<Program>().unwrap_err();
+        (e.line, e.column, e.message)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let program: Program = "forward 5\r\n\n  # comment\ndown 3 # dive\nup 1\nback 2\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            program.commands().collect::<Vec<_>>(),
+            vec![
+                Direction::Forward(5),
+                Direction::Down(3),
+                Direction::Up(1),
+                Direction::Back(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_negative_amounts() {
+        let program: Program = "forward -5\nrepeat 2 { down -1 }\n".parse().unwrap();
+        assert_eq!(
+            program.commands().collect::<Vec<_>>(),
+            vec![
+                Direction::Forward(-5),
+                Direction::Down(-1),
+                Direction::Down(-1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_amounts_agree_with_direction() {
+        for line in [
+            "forward -5",
+            "down +3",
+            "up 0",
+            "back 2147483647",
+            "up -2147483648",
+        ] {
+            let program: Program = line.parse().unwrap();
+            assert_eq!(
+                program.commands().collect::<Vec<_>>(),
+                vec![line.parse::<Direction>().unwrap()]
+            );
+        }
+        for line in ["forward 2147483648", "down -2147483649", "up - 3", "up --3"] {
+            assert!(line.parse::<Program>().is_err());
+            assert!(line.parse::<Direction>().is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_repeat() {
+        let program: Program = "repeat 2 {\n  forward 1\n  repeat 2 { down 1 }\n}\nup 1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            program.commands().collect::<Vec<_>>(),
+            vec![
+                Direction::Forward(1),
+                Direction::Down(1),
+                Direction::Down(1),
+                Direction::Forward(1),
+                Direction::Down(1),
+                Direction::Down(1),
+                Direction::Up(1),
+            ]
+        );
+        let program: Program = "repeat 0 { forward 1 }\nrepeat 3 {}".parse().unwrap();
+        assert_eq!(program.commands().count(), 0);
+    }
+
+    #[test]
+    fn test_command_count() {
+        let program: Program = "repeat 2 {\n  forward 1\n  repeat 2 { down 1 }\n}\nup 1"
+            .parse()
+            .unwrap();
+        assert_eq!(program.command_count(), Some(7));
+        let nested = "repeat 4000000000 { ".repeat(3) + "up 1" + &" }".repeat(3);
+        assert_eq!(nested.parse::<Program>().unwrap().command_count(), None);
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth: usize| "repeat 1 {".repeat(depth) + &"}".repeat(depth);
+        assert!(nested(MAX_DEPTH).parse::<Program>().is_ok());
+        assert_eq!(
+            parse_error(&nested(MAX_DEPTH + 1)),
+            (
+                1,
+                10 * MAX_DEPTH + 8,
+                "repeat blocks are nested too deeply".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_error("forward 5\nsideways 3"),
+            (2, 1, "unknown command `sideways`".to_string())
+        );
+        assert_eq!(parse_error("down"), (1, 5, "expected amount".to_string()));
+        assert_eq!(parse_error("up - 3"), (1, 4, "expected amount".to_string()));
+        assert_eq!(
+            parse_error("forward 5 down 3"),
+            (1, 11, "expected a line break".to_string())
+        );
+        assert_eq!(
+            parse_error("repeat 2 { up 1 } up 1"),
+            (1, 19, "expected a line break".to_string())
+        );
+        assert_eq!(
+            parse_error("forward 3000000000"),
+            (1, 9, "amount `3000000000` is out of range".to_string())
+        );
+        assert_eq!(
+            parse_error("\nrepeat 2 {\n forward 1\n"),
+            (2, 10, "unclosed `{`".to_string())
+        );
+        assert_eq!(
+            parse_error("repeat 2 {\n    repeat 3 { forward 1 }\n"),
+            (1, 10, "unclosed `{`".to_string())
+        );
+        assert_eq!(
+            parse_error("repeat 2 forward 1"),
+            (1, 10, "expected `{`".to_string())
+        );
+        assert_eq!(
+            parse_error("forward 1\n}"),
+            (2, 1, "expected a command or end of input".to_string())
+        );
+        assert_eq!(
+            parse_error("5"),
+            (1, 1, "expected a command or end of input".to_string())
+        );
+    }
+}
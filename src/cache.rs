@@ -0,0 +1,205 @@
+// Answers that were already computed, on disk, so re-running days whose code
+// and input didn't change is instant. Entries are kept per build in
+// `<root>/<profile>/<fingerprint>/`, with the fingerprint a hash of the
+// executable's size and modification time, and named after the year, day,
+// part and a hash of the input. Only answers are kept; errors and panics are
+// computed again every time.
+
+use crate::registry::Day;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::UNIX_EPOCH;
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is the same in every Rust
+/// release.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A hash of the running executable's size and modification time, which
+/// change whenever cargo relinks it for new code, inputs or features. That's
+/// much cheaper than hashing the executable itself.
+pub fn fingerprint() -> io::Result<String> {
+    let metadata = fs::metadata(std::env::current_exe()?)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let key = format!("{} {}", metadata.len(), modified.as_nanos());
+    Ok(format!("{:016x}", hash(key.as_bytes())))
+}
+
+/// The profile the running executable was built with, as far as the cache is
+/// concerned. Debug and release builds live side by side, so each keeps its
+/// own cache.
+pub fn profile() -> &'static str {
+    if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Cache {
+    /// The cache for the build of `profile` with `fingerprint` under
+    /// `root`, removing those of earlier builds of the same profile, which
+    /// can't be hit anymore since cargo replaced their executable.
+    pub fn open(root: &Path, profile: &str, fingerprint: &str) -> io::Result<Cache> {
+        let profile_dir = root.join(profile);
+        let dir = profile_dir.join(fingerprint);
+        fs::create_dir_all(&dir)?;
+        for entry in fs::read_dir(&profile_dir)? {
+            let path = entry?.path();
+            if path != dir && path.is_dir() {
+                fs::remove_dir_all(path)?;
+            }
+        }
+        Ok(Cache {
+            dir,
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        })
+    }
+
+    /// Removes every build's cache under `root`.
+    pub fn clear(root: &Path) -> io::Result<()> {
+        match fs::remove_dir_all(root) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Where part `index + 1` of `day` on `input` is kept.
+    fn path(&self, day: &Day, index: usize, input: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}_part_{}_{:016x}",
+            day.name(),
+            index + 1,
+            hash(input.as_bytes())
+        ))
+    }
+
+    /// The answer of part `index + 1` of `day` on `input`, if it's cached.
+    pub fn get(&self, day: &Day, index: usize, input: &str) -> Option<String> {
+        let answer = fs::read_to_string(self.path(day, index, input)).ok();
+        let counter = if answer.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        answer
+    }
+
+    pub fn put(&self, day: &Day, index: usize, input: &str, answer: &str) {
+        // Failing to cache an answer only means computing it again.
+        let _ = fs::write(self.path(day, index, input), answer);
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Display for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hits, misses) = (self.hits(), self.misses());
+        write!(
+            f,
+            "answer cache: {} hit{}, {} miss{}",
+            hits,
+            if hits == 1 { "" } else { "s" },
+            misses,
+            if misses == 1 { "" } else { "es" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::Outcome;
+    use crate::registry;
+    use crate::report::Record;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint().unwrap(), fingerprint().unwrap());
+    }
+
+    #[test]
+    fn test_cache() {
+        let root = std::env::temp_dir().join(format!("aoc_cache_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let day = registry::find(2020, 5).unwrap();
+
+        let cache = Cache::open(&root, "debug", "a").unwrap();
+        assert_eq!(cache.get(day, 0, "FBFBBFFRLR\n"), None);
+        cache.put(day, 0, "FBFBBFFRLR\n", "357");
+        assert_eq!(cache.get(day, 0, "FBFBBFFRLR\n"), Some("357".to_string()));
+        assert_eq!(cache.get(day, 1, "FBFBBFFRLR\n"), None);
+        assert_eq!(cache.get(day, 0, "BFFFBBFRRR\n"), None);
+        assert_eq!(cache.to_string(), "answer cache: 1 hit, 3 misses");
+
+        let release = Cache::open(&root, "release", "c").unwrap();
+        release.put(day, 0, "FBFBBFFRLR\n", "357");
+        let cache = Cache::open(&root, "debug", "b").unwrap();
+        assert!(!root.join("debug").join("a").exists());
+        assert!(root.join("release").join("c").exists());
+        assert_eq!(cache.get(day, 0, "FBFBBFFRLR\n"), None);
+
+        Cache::clear(&root).unwrap();
+        assert!(!root.exists());
+        Cache::clear(&root).unwrap();
+    }
+
+    #[test]
+    fn test_solve_cached() {
+        let root = std::env::temp_dir().join(format!("aoc_solve_cached_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let day = registry::find(2020, 5).unwrap();
+        let cache = Cache::open(&root, "debug", "a").unwrap();
+
+        let solve = |input| Record::solve(day, 0, "input", input, Some("989"), Some(&cache));
+        assert_eq!(
+            solve(day.input).outcome,
+            Outcome::Correct("989".to_string())
+        );
+        assert_eq!(
+            solve(day.input).outcome,
+            Outcome::Correct("989".to_string())
+        );
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // Errors aren't cached.
+        for _ in 0..2 {
+            let r = Record::solve(day, 1, "empty", "", None, Some(&cache));
+            assert_eq!(r.error(), Some("no free seat between two taken ones"));
+        }
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+// Talks to adventofcode.com, or whatever `AOC_BASE_URL` points to, through
+// `curl`, with the session cookie from `AOC_SESSION` passed on its standard
+// input so that it doesn't show up in the process list.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+fn base_url_from_env() -> String {
+    std::env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    /// Without a trailing slash, e.g. `https://adventofcode.com`.
+    pub base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    /// A client for `AOC_BASE_URL`, or adventofcode.com, logged in with the
+    /// session cookie in `AOC_SESSION`.
+    pub fn from_env() -> Result<Self, String> {
+        let session = std::env::var("AOC_SESSION").map_err(|_| {
+            "set AOC_SESSION to the `session` cookie of adventofcode.com".to_string()
+        })?;
+        Ok(Client::new(&base_url_from_env(), &session))
+    }
+
+    /// Like `from_env`, but logged out if `AOC_SESSION` isn't set, which is
+    /// enough for what's public, like the first part of a puzzle.
+    pub fn from_env_or_anonymous() -> Self {
+        let session = std::env::var("AOC_SESSION").unwrap_or_default();
+        Client::new(&base_url_from_env(), &session)
+    }
+
+    /// The body of `GET <base url><path>`.
+    pub fn get(&self, path: &str) -> Result<String, String> {
+        self.request(path, &[])
+    }
+
+    /// The body of `POST <base url><path>` with `form` URL-encoded.
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        self.request(path, form)
+    }
+
+    fn request(&self, path: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut curl = Command::new("curl");
+        curl.args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .args(["--user-agent", env!("CARGO_PKG_REPOSITORY")]);
+        for (name, value) in form {
+            curl.arg("--data-urlencode")
+                .arg(format!("{}={}", name, value));
+        }
+        let mut child = curl
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("can't run curl: {}", e))?;
+        let config = if self.session.is_empty() {
+            String::new()
+        } else {
+            format!("cookie = \"session={}\"\n", self.session.replace('"', ""))
+        };
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(config.as_bytes())
+            .map_err(|e| format!("can't run curl: {}", e))?;
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("can't run curl: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// A server on localhost that answers one request per body in `bodies`, and
+/// returns the requests it got when joined.
+#[cfg(test)]
+pub(crate) fn fake_server(
+    bodies: Vec<(u16, &'static str)>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, body) in bodies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            requests.push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {} Whatever\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (base_url, server)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests() {
+        let (base_url, server) = fake_server(vec![(200, "puzzle"), (200, "ok"), (404, "")]);
+        let client = Client::new(&format!("{}/", base_url), "abc123\n");
+        assert_eq!(client.get("/2020/day/5"), Ok("puzzle".to_string()));
+        assert_eq!(
+            client.post("/2020/day/5/answer", &[("level", "1"), ("answer", "a b&c")]),
+            Ok("ok".to_string())
+        );
+        let error = client.get("/2020/day/26").unwrap_err();
+        assert!(error.starts_with(&format!("{}/2020/day/26: ", base_url)));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2020/day/5 HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc123\r\n"));
+        assert!(requests[0].contains(&format!("User-Agent: {}\r\n", env!("CARGO_PKG_REPOSITORY"))));
+        assert!(requests[1].starts_with("POST /2020/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=1&answer=a+b%26c"));
+    }
+}
@@ -0,0 +1,253 @@
+// Runtime support for `#[derive(AocParse)]`.
+
+use crate::parse::ParseError;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Why a line didn't match its format, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    /// One-based column, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl FormatError {
+    /// The error as a `ParseError` for the line it was found on.
+    pub fn on_line(self, line: usize) -> ParseError {
+        ParseError {
+            line,
+            column: self.column,
+            message: self.message,
+        }
+    }
+}
+
+/// Walks a line from left to right, matching the literal text of a format and
+/// parsing the fields in between.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Cursor { input, pos: 0 }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn error(&self, pos: usize, message: impl Into<String>) -> FormatError {
+        FormatError {
+            column: self.input[..pos].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), FormatError> {
+        if self.rest().starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(self.pos, format!("expected `{}`", literal)))
+        }
+    }
+
+    /// Takes the text up to the first occurrence of `until`, or all of the
+    /// remaining text if `until` is `None`.
+    fn take(&mut self, name: &str, until: Option<&str>) -> Result<(usize, &'a str), FormatError> {
+        let start = self.pos;
+        let len = match until {
+            Some(literal) => self.rest().find(literal).ok_or_else(|| {
+                self.error(
+                    self.input.len(),
+                    format!("expected `{}` after {}", literal, name),
+                )
+            })?,
+            None => self.rest().len(),
+        };
+        self.pos += len;
+        Ok((start, &self.input[start..self.pos]))
+    }
+
+    fn parse<T>(&self, name: &str, pos: usize, text: &str) -> Result<T, FormatError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.parse()
+            .map_err(|e| self.error(pos, format!("invalid {} `{}`: {}", name, text, e)))
+    }
+
+    /// Parses a single field that ends where `until` starts.
+    pub fn field<T>(&mut self, name: &str, until: Option<&str>) -> Result<T, FormatError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (start, text) = self.take(name, until)?;
+        self.parse(name, start, text)
+    }
+
+    /// Parses a `sep`-separated list of values that ends where `until` starts.
+    pub fn repeated<T>(
+        &mut self,
+        name: &str,
+        sep: &str,
+        until: Option<&str>,
+    ) -> Result<Vec<T>, FormatError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (start, text) = self.take(name, until)?;
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pos = start;
+        text.split(sep)
+            .map(|item| {
+                let value = self.parse(name, pos, item);
+                pos += item.len() + sep.len();
+                value
+            })
+            .collect()
+    }
+
+    /// The first whitespace-separated word of the remaining text.
+    pub fn leading_word(&self) -> &'a str {
+        self.rest().split_whitespace().next().unwrap_or("")
+    }
+
+    pub fn finish(&self) -> Result<(), FormatError> {
+        if self.pos < self.input.len() {
+            Err(self.error(self.pos, format!("unexpected `{}`", self.rest())))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AocParse;
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(format = "{min}-{max} {letter}: {password}")]
+    struct Record {
+        min: usize,
+        max: usize,
+        letter: char,
+        password: String,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(format = "{0} bags contain {1:, } {{total}}")]
+    struct Rule(String, Vec<u32>);
+
+    #[derive(Debug, PartialEq, AocParse)]
+    enum Command {
+        Forward(i32),
+        #[aoc(format = "turn {0} by {1}")]
+        Turn(char, u16),
+        Stop,
+    }
+
+    fn err<T: std::fmt::Debug>(r: Result<T, super::FormatError>) -> (usize, String) {
+        let e = r.unwrap_err();
+        (e.column, e.message)
+    }
+
+    #[test]
+    fn test_struct() {
+        assert_eq!(
+            "1-3 a: abcde".parse::<Record>(),
+            Ok(Record {
+                min: 1,
+                max: 3,
+                letter: 'a',
+                password: "abcde".to_string()
+            })
+        );
+        assert_eq!(
+            err("1-x a: abcde".parse::<Record>()),
+            (
+                3,
+                "invalid max `x`: invalid digit found in string".to_string()
+            )
+        );
+        assert_eq!(
+            err("1-3 a abcde".parse::<Record>()),
+            (12, "expected `: ` after letter".to_string())
+        );
+        assert_eq!(
+            err("1-3 ab: abcde".parse::<Record>()),
+            (
+                5,
+                "invalid letter `ab`: too many characters in string".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_repeated() {
+        assert_eq!(
+            "red bags contain 1, 2, 3 {total}".parse::<Rule>(),
+            Ok(Rule("red".to_string(), vec![1, 2, 3]))
+        );
+        assert_eq!(
+            "red bags contain  {total}".parse::<Rule>(),
+            Ok(Rule("red".to_string(), vec![]))
+        );
+        assert_eq!(
+            err("red bags contain 1, x {total}".parse::<Rule>()),
+            (
+                21,
+                "invalid 1 `x`: invalid digit found in string".to_string()
+            )
+        );
+        assert_eq!(
+            err("red bags contain 1 {total} more".parse::<Rule>()),
+            (27, "unexpected ` more`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_enum() {
+        assert_eq!("forward 5".parse::<Command>(), Ok(Command::Forward(5)));
+        assert_eq!(
+            "turn L by 90".parse::<Command>(),
+            Ok(Command::Turn('L', 90))
+        );
+        assert_eq!("stop".parse::<Command>(), Ok(Command::Stop));
+        assert_eq!(
+            err("reverse 5".parse::<Command>()),
+            (
+                1,
+                "unknown `reverse`, expected one of `forward`, `turn`, `stop`".to_string()
+            )
+        );
+        assert_eq!(
+            err("forward -".parse::<Command>()),
+            (
+                9,
+                "invalid 0 `-`: invalid digit found in string".to_string()
+            )
+        );
+        assert_eq!(
+            err("stop now".parse::<Command>()),
+            (5, "unexpected ` now`".to_string())
+        );
+    }
+}
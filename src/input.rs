@@ -0,0 +1,184 @@
+// Helpers for reading puzzle inputs that tolerate a UTF-8 byte order mark,
+// CRLF line endings and trailing whitespace, as left behind by some editors.
+
+use crate::parse::ParseError;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The input without a leading byte order mark.
+pub fn strip_bom(input: &str) -> &str {
+    input.strip_prefix('\u{feff}').unwrap_or(input)
+}
+
+/// Every line with its line ending and trailing whitespace removed, blank
+/// lines included.
+fn raw_lines(input: &str) -> impl Iterator<Item = &str> {
+    strip_bom(input).lines().map(str::trim_end)
+}
+
+/// The non-blank lines of the input, without line endings or trailing
+/// whitespace.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    raw_lines(input).filter(|l| !l.is_empty())
+}
+
+/// Like `lines`, but with the one-based line number of each line.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    raw_lines(input)
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| (i + 1, l))
+}
+
+/// Groups of lines separated by one or more blank lines. Each block is a
+/// slice of the input, so it may still contain `\r\n` line endings.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let input = strip_bom(input);
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        // Skip blank lines before the block.
+        loop {
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if rest.is_empty() || !rest[..line_end].trim().is_empty() {
+                break;
+            }
+            rest = &rest[line_end..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        while end < rest.len() {
+            let line_end = rest[end..].find('\n').map_or(rest.len(), |i| end + i + 1);
+            if rest[end..line_end].trim().is_empty() {
+                break;
+            }
+            end = line_end;
+        }
+        let block = rest[..end].trim_end();
+        rest = &rest[end..];
+        Some(block)
+    })
+}
+
+/// Like `blocks`, but with the one-based line number each block starts on.
+pub fn numbered_blocks(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let stripped = strip_bom(input);
+    let (mut offset, mut line) = (0, 1);
+    blocks(input).map(move |block| {
+        let start = block.as_ptr() as usize - stripped.as_ptr() as usize;
+        line += stripped[offset..start].matches('\n').count();
+        offset = start;
+        (line, block)
+    })
+}
+
+/// Parses every non-blank line as a number.
+pub fn numbers<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbered_lines(input)
+        .map(|(line, l)| {
+            let trimmed = l.trim_start();
+            trimmed.parse().map_err(|e| ParseError {
+                line,
+                column: l.len() - trimmed.len() + 1,
+                message: format!("invalid number `{}`: {}", trimmed, e),
+            })
+        })
+        .collect()
+}
+
+/// A rectangular map of ASCII characters, such as a tree map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<'a> {
+    rows: Vec<&'a [u8]>,
+}
+
+impl<'a> Grid<'a> {
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        self.rows.get(y)?.get(x).copied()
+    }
+
+    pub fn rows(&self) -> &[&'a [u8]] {
+        &self.rows
+    }
+}
+
+/// The non-blank lines of the input as a grid of bytes.
+pub fn grid(input: &str) -> Grid<'_> {
+    Grid {
+        rows: lines(input).map(str::as_bytes).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MIXED: &str = "\u{feff}1\r\n2  \n\r\n  \n3\r\n\r\n\n4\n \n";
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines(MIXED).collect::<Vec<_>>(), vec!["1", "2", "3", "4"]);
+        assert_eq!(lines("").count(), 0);
+        assert_eq!(lines("\u{feff}").count(), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(blocks(MIXED).collect::<Vec<_>>(), vec!["1\r\n2", "3", "4"]);
+        assert_eq!(blocks("a\nb\n\nc\n").collect::<Vec<_>>(), vec!["a\nb", "c"]);
+        assert_eq!(
+            blocks("a\r\nb\r\n\r\nc\r\n").collect::<Vec<_>>(),
+            vec!["a\r\nb", "c"]
+        );
+        assert_eq!(blocks("\n\na\n \t\nb").collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("\r\n\r\n").count(), 0);
+    }
+
+    #[test]
+    fn test_numbered() {
+        assert_eq!(
+            numbered_lines(MIXED).collect::<Vec<_>>(),
+            vec![(1, "1"), (2, "2"), (5, "3"), (8, "4")]
+        );
+        assert_eq!(
+            numbered_blocks(MIXED).collect::<Vec<_>>(),
+            vec![(1, "1\r\n2"), (5, "3"), (8, "4")]
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(numbers::<i64>(MIXED), Ok(vec![1, 2, 3, 4]));
+        assert!(numbers::<u8>(" -1 \n").is_err());
+        assert!(numbers::<u8>("1\nx\n").is_err());
+        assert_eq!(
+            numbers::<u8>("1\n\n  300\n").unwrap_err().to_string(),
+            "3:3: invalid number `300`: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = grid("\u{feff}.#\r\n#.\r\n");
+        assert_eq!(grid.width(), 2);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(1, 0), Some(b'#'));
+        assert_eq!(grid.get(1, 1), Some(b'.'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+}
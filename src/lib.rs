@@ -1,3 +1,9 @@
+extern crate self as aoc;
+
+pub use aoc_derive::AocParse;
+
+pub mod allocations;
+pub mod answers;
 pub mod aoc_2020_01;
 pub mod aoc_2020_02;
 pub mod aoc_2020_03;
@@ -6,3 +12,18 @@ pub mod aoc_2020_05;
 pub mod aoc_2020_06;
 pub mod aoc_2021_01;
 pub mod aoc_2021_02;
+pub mod cache;
+pub mod client;
+pub mod format;
+pub mod input;
+pub mod matrix;
+pub mod overflow;
+pub mod parse;
+pub mod puzzle;
+pub mod registry;
+pub mod report;
+pub mod rng;
+pub mod submit;
+pub mod timing;
+pub mod watch;
+pub mod window;
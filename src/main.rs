@@ -0,0 +1,575 @@
+// Runs the registered days from the command line, e.g.
+// `cargo run -- run 2020 5 --example gap` or `cargo run -- examples 2020 5`.
+
+use aoc::allocations::Stats;
+use aoc::cache::{self, Cache};
+use aoc::client::Client;
+use aoc::matrix::{self, Outcome};
+use aoc::puzzle;
+use aoc::registry::{self, Day};
+use aoc::report::{self, Record};
+use aoc::submit;
+use aoc::timing;
+use aoc::watch::{self, Snapshot};
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitCode, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "usage:
+    aoc run <year> <day> [--example <name>]
+    aoc examples <year> <day>
+    aoc matrix [<inputs dir>]
+    aoc generate <year> <day> [--size <n>] [--seed <n>]
+    aoc verify <year> <day> [--size <n>] [--seeds <n>]
+    aoc time [<year>] [--budget <duration>] [--threads <n>]
+    aoc watch <year> <day> [--interval <duration>]
+    aoc submit <year> <day> <part> [<answer>]
+    aoc puzzle <year> <day>
+
+run, examples, matrix and time take --format text|json|junit
+run, examples and matrix cache answers in target/answer-cache, which
+--no-cache bypasses and --clear-cache empties first";
+
+fn find_day(year: Option<String>, day: Option<String>) -> Result<&'static Day, String> {
+    let (Some(year), Some(day)) = (year, day) else {
+        return Err(USAGE.to_string());
+    };
+    let year = year
+        .parse()
+        .map_err(|_| format!("invalid year `{}`", year))?;
+    let day = day.parse().map_err(|_| format!("invalid day `{}`", day))?;
+    registry::find(year, day).ok_or_else(|| format!("no solution for {} day {}", year, day))
+}
+
+/// Parses `--name <n>` options, one for each of `names`, starting from
+/// `defaults`.
+fn numeric_options<const N: usize>(
+    args: &mut impl Iterator<Item = String>,
+    names: [&str; N],
+    defaults: [u64; N],
+) -> Result<[u64; N], String> {
+    let mut values = defaults;
+    while let Some(arg) = args.next() {
+        let i = names
+            .iter()
+            .position(|name| *name == arg)
+            .ok_or_else(|| format!("unexpected argument `{}`\n{}", arg, USAGE))?;
+        let value = args.next().ok_or(USAGE)?;
+        values[i] = value
+            .parse()
+            .map_err(|_| format!("invalid {} `{}`", arg, value))?;
+    }
+    Ok(values)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Junit,
+}
+
+impl Format {
+    /// Takes `--format <name>` out of `args`, wherever it is.
+    fn take(args: &mut Vec<String>) -> Result<Format, String> {
+        let Some(i) = args.iter().position(|arg| arg == "--format") else {
+            return Ok(Format::Text);
+        };
+        let name = args.get(i + 1).ok_or(USAGE)?;
+        let format = match name.as_str() {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "junit" => Format::Junit,
+            _ => return Err(format!("unknown format `{}`\n{}", name, USAGE)),
+        };
+        args.drain(i..i + 2);
+        Ok(format)
+    }
+
+    /// Prints `records` unless the format is text, and returns whether it
+    /// did.
+    fn print(self, records: &[Record]) -> bool {
+        match self {
+            Format::Text => return false,
+            Format::Json => print!("{}", report::to_json(records)),
+            Format::Junit => print!("{}", report::to_junit(records)),
+        }
+        true
+    }
+}
+
+/// `--no-cache` and `--clear-cache`, for the commands that cache answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CacheOptions {
+    bypass: bool,
+    clear: bool,
+}
+
+impl CacheOptions {
+    /// Takes the options out of `args`, wherever they are.
+    fn take(args: &mut Vec<String>) -> CacheOptions {
+        let mut take = |flag: &str| {
+            let len = args.len();
+            args.retain(|arg| arg != flag);
+            args.len() != len
+        };
+        CacheOptions {
+            bypass: take("--no-cache"),
+            clear: take("--clear-cache"),
+        }
+    }
+
+    /// The cache in `target/answer-cache`, cleared first if asked to, or
+    /// `None` if it's bypassed.
+    fn open(self) -> Result<Option<Cache>, String> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("answer-cache");
+        let error = |e: io::Error| format!("{}: {}", root.display(), e);
+        if self.clear {
+            Cache::clear(&root).map_err(error)?;
+        }
+        if self.bypass {
+            return Ok(None);
+        }
+        let fingerprint =
+            cache::fingerprint().map_err(|e| format!("can't fingerprint the build: {}", e))?;
+        Cache::open(&root, cache::profile(), &fingerprint)
+            .map(Some)
+            .map_err(error)
+    }
+}
+
+/// ` [3 allocations, ...]` when allocations are counted.
+fn bracketed(stats: Option<Stats>) -> String {
+    stats.map_or_else(String::new, |stats| format!("  [{}]", stats))
+}
+
+/// Runs both parts on `input`, named `name`, comparing them with `expected`
+/// where given.
+fn run_parts(
+    day: &Day,
+    name: &str,
+    input: &str,
+    expected: [Option<&str>; 2],
+    cache: Option<&Cache>,
+) -> Vec<Record> {
+    (0..day.parts.len())
+        .map(|i| Record::solve(day, i, name, input, expected[i], cache))
+        .collect()
+}
+
+fn print_parts(records: &[Record]) {
+    for r in records {
+        let status = match (&r.outcome, &r.expected) {
+            (Outcome::Correct(_), _) => " (ok)".to_string(),
+            (_, Some(expected)) => format!(" (expected {})", expected),
+            (_, None) => String::new(),
+        };
+        let answer = match &r.outcome {
+            Outcome::Failed(e) => format!("error: {}", e),
+            _ => r.answer().unwrap_or_default().to_string(),
+        };
+        println!(
+            "part {}: {}{}{}",
+            r.part,
+            answer,
+            status,
+            bracketed(r.allocations)
+        );
+    }
+}
+
+/// Whether every part with an expected answer gave it.
+fn all_ok(records: &[Record]) -> bool {
+    records
+        .iter()
+        .all(|r| r.expected.is_none() || matches!(r.outcome, Outcome::Correct(_)))
+}
+
+fn run(
+    args: &mut impl Iterator<Item = String>,
+    format: Format,
+    cache: Option<&Cache>,
+) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let records = match args.next().as_deref() {
+        None => run_parts(
+            day,
+            "input",
+            day.input,
+            [day.parts[0].answer, day.parts[1].answer],
+            cache,
+        ),
+        Some("--example") => {
+            let name = args.next().ok_or(USAGE)?;
+            let example = day
+                .find_example(&name)
+                .ok_or_else(|| format!("{} has no example `{}`", day.name(), name))?;
+            run_parts(day, example.name, example.input, example.answers, cache)
+        }
+        Some(arg) => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+    };
+    if !format.print(&records) {
+        print_parts(&records);
+    }
+    Ok(all_ok(&records))
+}
+
+fn examples(
+    args: &mut impl Iterator<Item = String>,
+    format: Format,
+    cache: Option<&Cache>,
+) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let mut records = Vec::new();
+    for example in day.all_examples() {
+        let parts = run_parts(day, example.name, example.input, example.answers, cache);
+        if format == Format::Text {
+            println!("{}:", example.name);
+            print_parts(&parts);
+        }
+        records.extend(parts);
+    }
+    format.print(&records);
+    Ok(all_ok(&records))
+}
+
+/// Prints a generated input, e.g. to save as a user's input for `matrix`.
+fn generate(args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let [size, seed] = numeric_options(args, ["--size", "--seed"], [1000, 0])?;
+    let input = day
+        .generated(seed, size as usize)
+        .ok_or_else(|| format!("{} has no generator", day.name()))?;
+    print!("{}", input);
+    Ok(true)
+}
+
+/// Checks both parts against their oracles on inputs generated from seeds
+/// `0..seeds`.
+fn verify(args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let [size, seeds] = numeric_options(args, ["--size", "--seeds"], [1000, 10])?;
+    let mut ok = true;
+    for seed in 0..seeds {
+        let input = day
+            .generated(seed, size as usize)
+            .ok_or_else(|| format!("{} has no generator", day.name()))?;
+        for (i, part) in day.parts.iter().enumerate() {
+            let oracle = part
+                .oracle
+                .ok_or_else(|| format!("{} part {} has no oracle", day.name(), i + 1))?;
+            let start = Instant::now();
+            let answer = matrix::solve_caught(part.solve, &input);
+            let elapsed = start.elapsed();
+            let expected = matrix::solve_caught(oracle, &input);
+            let status = if answer == expected {
+                "ok".to_string()
+            } else {
+                ok = false;
+                format!("MISMATCH {:?} (oracle {:?})", answer, expected)
+            };
+            println!(
+                "seed {}  part {}: {} in {:.2?}",
+                seed,
+                i + 1,
+                status,
+                elapsed
+            );
+        }
+    }
+    Ok(ok)
+}
+
+/// Runs `aoc <args>` through `cargo run`, so it's rebuilt first, and returns
+/// its output. Build errors and warnings go straight to stderr. It builds
+/// into `target/watch` rather than over the `aoc` running `watch`, which
+/// Windows doesn't allow.
+fn cargo_run(args: &[&str]) -> Result<String, String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let target_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("watch");
+    let output = Command::new(cargo)
+        .args(["run", "--quiet", "--target-dir"])
+        .arg(target_dir)
+        .arg("--")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("can't run cargo: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Re-solves the examples and input of a day whenever a file in its module
+/// directory changes, marking the answers that changed with `*`.
+fn watch(args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let interval = match args.next().as_deref() {
+        None => Duration::from_millis(500),
+        Some("--interval") => timing::parse_duration(&args.next().ok_or(USAGE)?)?,
+        Some(arg) => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+    };
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(day.name());
+    let (year, number) = (day.year.to_string(), day.day.to_string());
+    let mut snapshot = None;
+    let mut previous = Vec::new();
+    loop {
+        let current = Snapshot::take(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        if let Some(earlier) = &snapshot {
+            if current == *earlier {
+                thread::sleep(interval);
+                continue;
+            }
+            for path in current.changed(earlier) {
+                println!("changed: {}", path.display());
+            }
+        }
+        let mut lines = watch::compact(
+            &cargo_run(&["examples", &year, &number, "--no-cache"])?,
+            "example",
+        );
+        lines.extend(watch::compact(
+            &cargo_run(&["run", &year, &number, "--no-cache"])?,
+            "input",
+        ));
+        if snapshot.is_none() {
+            lines.iter().for_each(|line| println!("  {}", line));
+        } else {
+            watch::mark_changes(&previous, &lines)
+                .iter()
+                .for_each(|line| println!("{}", line));
+        }
+        println!("watching {} for changes", dir.display());
+        snapshot = Some(current);
+        previous = lines;
+    }
+}
+
+/// Submits an answer, or the part's answer on the day's input, unless it's
+/// ruled out by earlier attempts.
+fn submit(args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+    let day = find_day(args.next(), args.next())?;
+    let part = args.next().ok_or(USAGE)?;
+    let index = match part.as_str() {
+        "1" => 0,
+        "2" => 1,
+        _ => return Err(format!("invalid part `{}`", part)),
+    };
+    let answer = match args.next() {
+        Some(answer) => answer,
+        None => matrix::solve_caught(day.parts[index].solve, day.input)
+            .map_err(|e| format!("part {} failed: {}", part, e))?,
+    };
+    if let Some(arg) = args.next() {
+        return Err(format!("unexpected argument `{}`\n{}", arg, USAGE));
+    }
+    let client = Client::from_env()?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("submissions");
+    let path = submit::attempts_path(&root, day.year, day.day);
+    println!("submitting {} for {} part {}", answer, day.name(), part);
+    let verdict = submit::submit(&client, &path, (day.year, day.day), index + 1, &answer)?;
+    println!("{}", verdict);
+    Ok(verdict == submit::Verdict::Correct)
+}
+
+/// Downloads a day's puzzle into its module directory, which doesn't need
+/// to exist yet.
+fn puzzle(args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+    let (Some(year), Some(day), None) = (args.next(), args.next(), args.next()) else {
+        return Err(USAGE.to_string());
+    };
+    let year: u16 = year
+        .parse()
+        .map_err(|_| format!("invalid year `{}`", year))?;
+    let day: u8 = match day.parse() {
+        Ok(day @ 1..=25) => day,
+        _ => return Err(format!("invalid day `{}`", day)),
+    };
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(format!("aoc_{}_{:02}", year, day));
+    let client = Client::from_env_or_anonymous();
+    for path in puzzle::download(&client, &dir, year, day)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(true)
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Correct(answer) => format!("ok {}", answer),
+        Outcome::Unverified(answer) => format!("? {}", answer),
+        Outcome::Wrong { answer, expected } => {
+            format!("WRONG {} (expected {})", answer, expected)
+        }
+        Outcome::Failed(e) => format!("FAILED {}", e),
+    }
+}
+
+/// Runs every day on the built-in inputs and everyone's inputs under `dir`.
+fn matrix(
+    args: &mut impl Iterator<Item = String>,
+    format: Format,
+    cache: Option<&Cache>,
+) -> Result<bool, String> {
+    let (dir, explicit) = match args.next() {
+        Some(dir) => (dir, true),
+        None => ("inputs".to_string(), false),
+    };
+    let mut inputs = matrix::builtin_inputs();
+    match matrix::read_inputs(Path::new(&dir)) {
+        Ok(users) => inputs.extend(users),
+        Err(e) if e.kind() == io::ErrorKind::NotFound && !explicit => {}
+        Err(e) => return Err(format!("{}: {}", dir, e)),
+    }
+    let rows = matrix::run(&inputs, cache);
+    let records = rows
+        .iter()
+        .flat_map(|row| row.records.iter().cloned())
+        .collect::<Vec<_>>();
+    let discrepancies = records
+        .iter()
+        .filter(|r| r.outcome.is_discrepancy())
+        .count();
+    if format.print(&records) {
+        return Ok(discrepancies == 0);
+    }
+    let width = inputs.iter().map(|i| i.user.len()).max().unwrap_or(0);
+    for row in &rows {
+        let input = row.input;
+        let cells = if row.day.is_none() {
+            "no solution".to_string()
+        } else {
+            row.records
+                .iter()
+                .map(|r| describe(&r.outcome))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+        println!(
+            "{:width$}  {}/{:02}  {}",
+            input.user,
+            input.year,
+            input.day,
+            cells,
+            width = width
+        );
+    }
+    println!("{} discrepancies in {} inputs", discrepancies, rows.len());
+    Ok(discrepancies == 0)
+}
+
+/// Runs every part of every day (or of one year) concurrently and prints
+/// them slowest first. Fails if they take longer than the budget in total,
+/// or if any answer is wrong.
+fn time(args: &mut impl Iterator<Item = String>, format: Format) -> Result<bool, String> {
+    let mut year = None;
+    let mut budget = Duration::from_secs(1);
+    let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--budget" => budget = timing::parse_duration(&args.next().ok_or(USAGE)?)?,
+            "--threads" => {
+                let n = args.next().ok_or(USAGE)?;
+                threads = n
+                    .parse()
+                    .map_err(|_| format!("invalid --threads `{}`", n))?;
+            }
+            _ if year.is_none() => {
+                year = Some(arg.parse().map_err(|_| format!("invalid year `{}`", arg))?)
+            }
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+    let days = registry::DAYS
+        .iter()
+        .copied()
+        .filter(|day| year.is_none_or(|year| day.year == year))
+        .collect::<Vec<_>>();
+    if days.is_empty() {
+        return Err(format!("no solutions for {}", year.unwrap_or_default()));
+    }
+
+    let start = Instant::now();
+    let mut records = timing::run(&days, threads);
+    let wall = start.elapsed();
+    let total = timing::total(&records);
+    let within = total <= budget;
+    let discrepancies = records
+        .iter()
+        .filter(|r| r.outcome.is_discrepancy())
+        .count();
+    if format.print(&records) {
+        return Ok(within && discrepancies == 0);
+    }
+    records.sort_by_key(|r| std::cmp::Reverse(r.duration));
+    for r in &records {
+        println!(
+            "{:>10.2?}  {} part {}  {}{}",
+            r.duration,
+            r.name(),
+            r.part,
+            describe(&r.outcome),
+            bracketed(r.allocations)
+        );
+    }
+    println!(
+        "{:.2?} in total for {} parts ({:.2?} on {} thread{})",
+        total,
+        records.len(),
+        wall,
+        threads,
+        if threads == 1 { "" } else { "s" }
+    );
+    if within {
+        println!("within the budget of {:?}", budget);
+    } else {
+        println!("OVER the budget of {:?} by {:.2?}", budget, total - budget);
+    }
+    Ok(within && discrepancies == 0)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let cache_options = CacheOptions::take(&mut args);
+    let result = Format::take(&mut args).and_then(|format| {
+        let mut args = args.into_iter();
+        match (args.next().as_deref(), format) {
+            (Some(command @ ("run" | "examples" | "matrix")), _) => {
+                let cache = cache_options.open()?;
+                let result = match command {
+                    "run" => run(&mut args, format, cache.as_ref()),
+                    "examples" => examples(&mut args, format, cache.as_ref()),
+                    _ => matrix(&mut args, format, cache.as_ref()),
+                };
+                if let Some(cache) = &cache {
+                    eprintln!("{}", cache);
+                }
+                result
+            }
+            _ if cache_options.bypass || cache_options.clear => Err(USAGE.to_string()),
+            (Some("time"), _) => time(&mut args, format),
+            (Some("watch"), Format::Text) => watch(&mut args),
+            (Some("submit"), Format::Text) => submit(&mut args),
+            (Some("puzzle"), Format::Text) => puzzle(&mut args),
+            (Some("generate"), Format::Text) => generate(&mut args),
+            (Some("verify"), Format::Text) => verify(&mut args),
+            _ => Err(USAGE.to_string()),
+        }
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
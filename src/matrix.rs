@@ -0,0 +1,303 @@
+// Runs every day against everyone's personal input, laid out as
+// `inputs/<user>/<year>/<day>.txt` with an optional `<day>.answers` sidecar
+// (see `answers`), to catch solutions that only work on one input.
+
+use crate::answers;
+use crate::cache::Cache;
+use crate::registry::{self, Day};
+use crate::report::Record;
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::Path;
+use std::sync::Once;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserInput {
+    pub user: String,
+    pub year: u16,
+    pub day: u8,
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+fn invalid_data(path: &Path, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), message),
+    )
+}
+
+/// The entries of `dir` whose names parse as `T`, with their names.
+fn entries<T: std::str::FromStr>(dir: &Path) -> io::Result<Vec<(T, String)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Ok(key) = name.parse() {
+            entries.push((key, name));
+        }
+    }
+    Ok(entries)
+}
+
+/// Reads every user's inputs under `root`, sorted by user, year and day.
+pub fn read_inputs(root: &Path) -> io::Result<Vec<UserInput>> {
+    let mut inputs = Vec::new();
+    for (user, _) in entries::<String>(root)? {
+        let user_dir = root.join(&user);
+        if !user_dir.is_dir() {
+            continue;
+        }
+        for (year, year_name) in entries::<u16>(&user_dir)? {
+            let year_dir = user_dir.join(year_name);
+            for entry in fs::read_dir(&year_dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|ext| ext != "txt") {
+                    continue;
+                }
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let day = stem
+                    .parse()
+                    .map_err(|_| invalid_data(&path, "expected a day number"))?;
+                let answers_path = path.with_extension("answers");
+                let answers = match fs::read_to_string(&answers_path) {
+                    Ok(text) => {
+                        answers::parse(&text).map_err(|e| invalid_data(&answers_path, e))?
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => [None, None],
+                    Err(e) => return Err(e),
+                };
+                inputs.push(UserInput {
+                    user: user.clone(),
+                    year,
+                    day,
+                    input: fs::read_to_string(&path)?,
+                    answers,
+                });
+            }
+        }
+    }
+    inputs.sort_by(|a, b| (&a.user, a.year, a.day).cmp(&(&b.user, b.year, b.day)));
+    Ok(inputs)
+}
+
+/// Every day's own `input.txt` as the inputs of a user named `builtin`.
+pub fn builtin_inputs() -> Vec<UserInput> {
+    registry::DAYS
+        .iter()
+        .map(|day| UserInput {
+            user: "builtin".to_string(),
+            year: day.year,
+            day: day.day,
+            input: day.input.to_string(),
+            answers: day.parts.each_ref().map(|p| p.answer.map(str::to_string)),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct(String),
+    /// There's no expected answer to compare with.
+    Unverified(String),
+    Wrong {
+        answer: String,
+        expected: String,
+    },
+    /// The part returned an error or panicked.
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn is_discrepancy(&self) -> bool {
+        matches!(self, Outcome::Wrong { .. } | Outcome::Failed(_))
+    }
+}
+
+thread_local! {
+    /// Whether this thread is in `solve_caught`, whose panics aren't printed.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Wraps the panic hook once so that it skips panics `solve_caught` turns
+/// into errors, and handles every other panic as before.
+fn quiet_caught_panics() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// Runs `solve`, turning a panic into an error so one bad input doesn't stop
+/// the whole matrix. The panic is reported only through that error.
+pub fn solve_caught(solve: registry::Solver, input: &str) -> Result<String, String> {
+    quiet_caught_panics();
+    let caught = CATCHING.with(|c| c.replace(true));
+    let result = panic::catch_unwind(|| solve(input));
+    CATCHING.with(|c| c.set(caught));
+    result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(format!("panicked: {}", message))
+    })
+}
+
+pub fn outcome(answer: Result<String, String>, expected: Option<&str>) -> Outcome {
+    match (answer, expected) {
+        (Err(e), _) => Outcome::Failed(e),
+        (Ok(answer), None) => Outcome::Unverified(answer),
+        (Ok(answer), Some(expected)) if answer == expected => Outcome::Correct(answer),
+        (Ok(answer), Some(expected)) => Outcome::Wrong {
+            answer,
+            expected: expected.to_string(),
+        },
+    }
+}
+
+pub struct Row<'a> {
+    pub input: &'a UserInput,
+    /// `None` when there's no solution for the day yet.
+    pub day: Option<&'static Day>,
+    /// One per part, named after the user.
+    pub records: Vec<Record>,
+}
+
+pub fn run<'a>(inputs: &'a [UserInput], cache: Option<&Cache>) -> Vec<Row<'a>> {
+    inputs
+        .iter()
+        .map(|input| {
+            let day = registry::find(input.year, input.day);
+            let records = day
+                .map(|day| {
+                    (0..day.parts.len())
+                        .map(|i| {
+                            let expected = input.answers[i].as_deref();
+                            Record::solve(day, i, &input.user, &input.input, expected, cache)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Row {
+                input,
+                day,
+                records,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn outcomes(row: &Row) -> Vec<Outcome> {
+        row.records.iter().map(|r| r.outcome.clone()).collect()
+    }
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_matrix() {
+        let root = temp_dir("matrix");
+        let gap = registry::find(2020, 5)
+            .unwrap()
+            .find_example("gap")
+            .unwrap();
+        write(root.join("alice/2020/05.txt"), gap.input);
+        write(
+            root.join("alice/2020/05.answers"),
+            "part_1: 11\npart_2: 10\n",
+        );
+        write(root.join("bob/2020/5.txt"), "FBFBBFFRLR\n");
+        write(root.join("bob/2020/25.txt"), "");
+        write(root.join("README.md"), "");
+
+        let inputs = read_inputs(&root).unwrap();
+        let keys = inputs
+            .iter()
+            .map(|i| (i.user.as_str(), i.year, i.day))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![("alice", 2020, 5), ("bob", 2020, 5), ("bob", 2020, 25)]
+        );
+
+        let rows = run(&inputs, None);
+        assert_eq!(
+            outcomes(&rows[0]),
+            vec![
+                Outcome::Wrong {
+                    answer: "12".to_string(),
+                    expected: "11".to_string()
+                },
+                Outcome::Correct("10".to_string()),
+            ]
+        );
+        assert_eq!(
+            outcomes(&rows[1]),
+            vec![
+                Outcome::Unverified("357".to_string()),
+                Outcome::Failed("no free seat between two taken ones".to_string()),
+            ]
+        );
+        assert!(rows[2].day.is_none());
+
+        write(root.join("carol/2020/1.answers"), "part_1 42\n");
+        write(root.join("carol/2020/1.txt"), "");
+        assert!(read_inputs(&root).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_sample_inputs() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let inputs = read_inputs(&root).unwrap();
+        assert!(inputs.iter().any(|input| input.user == "sample"));
+        assert!(run(&inputs, None)
+            .iter()
+            .flat_map(|row| &row.records)
+            .all(|r| matches!(r.outcome, Outcome::Correct(_))));
+    }
+
+    #[test]
+    fn test_solve_caught() {
+        assert_eq!(
+            solve_caught(|_| Ok("1".to_string()), ""),
+            Ok("1".to_string())
+        );
+        assert_eq!(
+            solve_caught(|_| panic!("boom"), ""),
+            Err("panicked: boom".to_string())
+        );
+    }
+
+    #[test]
+    fn test_builtin_inputs() {
+        let inputs = builtin_inputs();
+        assert_eq!(inputs.len(), registry::DAYS.len());
+        assert!(run(&inputs, None)
+            .iter()
+            .flat_map(|row| &row.records)
+            .all(|r| matches!(r.outcome, Outcome::Correct(_))));
+    }
+}
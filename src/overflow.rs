@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// An answer that doesn't fit in the integer type it is computed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Multiplies all factors, failing instead of wrapping around.
+pub fn checked_product<I>(factors: I) -> Result<u64, Overflow>
+where
+    I: IntoIterator,
+    I::Item: Into<u64>,
+{
+    factors
+        .into_iter()
+        .try_fold(1u64, |acc, n| acc.checked_mul(n.into()))
+        .ok_or(Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_product() {
+        assert_eq!(checked_product(Vec::<u32>::new()), Ok(1));
+        assert_eq!(checked_product([2u32, 3, 7]), Ok(42));
+        assert_eq!(
+            checked_product([u32::MAX, u32::MAX]),
+            Ok(18446744065119617025)
+        );
+        assert_eq!(checked_product([u32::MAX, u32::MAX, 2]), Err(Overflow));
+    }
+}
@@ -0,0 +1,588 @@
+// A small parser-combinator toolkit for puzzle inputs with nested structure.
+//
+// Single-line formats are usually simpler with `#[derive(AocParse)]`. For
+// anything nested, parsers are plain functions or closures taking an `Input`,
+// so recursive grammars are just functions that call themselves.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+
+/// A byte range of the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// The same error for a piece of a larger input that starts on `line`,
+    /// such as a block.
+    pub fn starting_at_line(self, line: usize) -> Self {
+        ParseError {
+            line: self.line + line - 1,
+            ..self
+        }
+    }
+}
+
+/// The furthest point any parser got to before failing. Backtracking
+/// combinators like `or` and `many0` hide failures, so the most useful error
+/// is usually the one furthest into the input.
+#[derive(Debug, Default)]
+struct Furthest {
+    offset: usize,
+    expected: Vec<String>,
+    message: Option<String>,
+}
+
+impl Furthest {
+    fn expected(&mut self, offset: usize, what: String) {
+        if offset > self.offset {
+            *self = Furthest::default();
+            self.offset = offset;
+        }
+        if offset == self.offset && !self.expected.contains(&what) {
+            self.expected.push(what);
+        }
+    }
+
+    fn message(&mut self, offset: usize, message: String) {
+        if offset >= self.offset {
+            self.offset = offset;
+            self.expected.clear();
+            self.message = Some(message);
+        }
+    }
+
+    fn abort(&mut self, offset: usize, message: String) {
+        self.offset = offset;
+        self.expected.clear();
+        self.message = Some(message);
+    }
+
+    fn describe(&self) -> String {
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+        match self.expected.as_slice() {
+            [] => "invalid input".to_string(),
+            [one] => format!("expected {}", one),
+            [init @ .., last] => format!("expected {} or {}", init.join(", "), last),
+        }
+    }
+}
+
+thread_local! {
+    static FURTHEST: RefCell<Furthest> = RefCell::new(Furthest::default());
+}
+
+/// A position in the source text.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+/// A parser failed; what went wrong is tracked by the `Input`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failure {
+    _private: (),
+}
+
+pub type PResult<'a, T> = Result<(T, Input<'a>), Failure>;
+
+impl<'a> Input<'a> {
+    pub fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    pub fn advance(self, bytes: usize) -> Input<'a> {
+        Input {
+            pos: self.pos + bytes,
+            ..self
+        }
+    }
+
+    /// Fails with "expected `what`" at this position.
+    pub fn expected<T>(self, what: impl Into<String>) -> PResult<'a, T> {
+        FURTHEST.with(|f| f.borrow_mut().expected(self.pos, what.into()));
+        Err(Failure { _private: () })
+    }
+
+    /// Fails with a custom message at this position.
+    pub fn fail<T>(self, message: impl Into<String>) -> PResult<'a, T> {
+        FURTHEST.with(|f| f.borrow_mut().message(self.pos, message.into()));
+        Err(Failure { _private: () })
+    }
+
+    /// Fails with a custom message at this position even if a parser got
+    /// further, for errors that explain the later ones, like an unclosed
+    /// bracket.
+    pub fn abort<T>(self, message: impl Into<String>) -> PResult<'a, T> {
+        FURTHEST.with(|f| f.borrow_mut().abort(self.pos, message.into()));
+        Err(Failure { _private: () })
+    }
+}
+
+pub trait Parser<'a, T>: Sized {
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U> {
+        move |input| {
+            let (value, rest) = self.parse(input)?;
+            Ok((f(value), rest))
+        }
+    }
+
+    /// Like `map`, but `f` can reject the value with a message that is
+    /// reported where the value starts.
+    fn try_map<U>(self, f: impl Fn(T) -> Result<U, String>) -> impl Parser<'a, U> {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse(input)?;
+            match f(value) {
+                Ok(value) => Ok((value, rest)),
+                Err(message) => input.fail(message),
+            }
+        }
+    }
+
+    /// Chooses the next parser based on the value of this one.
+    fn and_then<U, P: Parser<'a, U>>(self, f: impl Fn(T) -> P) -> impl Parser<'a, U> {
+        move |input| {
+            let (value, rest) = self.parse(input)?;
+            f(value).parse(rest)
+        }
+    }
+
+    fn then<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)> {
+        move |input| {
+            let (a, rest) = self.parse(input)?;
+            let (b, rest) = next.parse(rest)?;
+            Ok(((a, b), rest))
+        }
+    }
+
+    /// Runs `next` after this parser, keeping only this parser's value.
+    fn skip<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T> {
+        move |input| {
+            let (value, rest) = self.parse(input)?;
+            let (_, rest) = next.parse(rest)?;
+            Ok((value, rest))
+        }
+    }
+
+    /// Tries `other` from the same position if this parser fails.
+    fn or(self, other: impl Parser<'a, T>) -> impl Parser<'a, T> {
+        move |input| self.parse(input).or_else(|_| other.parse(input))
+    }
+
+    fn optional(self) -> impl Parser<'a, Option<T>> {
+        move |input| match self.parse(input) {
+            Ok((value, rest)) => Ok((Some(value), rest)),
+            Err(_) => Ok((None, input)),
+        }
+    }
+
+    fn spanned(self) -> impl Parser<'a, (T, Span)> {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse(input)?;
+            let span = Span {
+                start: input.offset(),
+                end: rest.offset(),
+            };
+            Ok(((value, span), rest))
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Input<'a>) -> PResult<'a, T>,
+{
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+pub fn tag<'a>(tag: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        if input.rest().starts_with(tag) {
+            Ok((&input.rest()[..tag.len()], input.advance(tag.len())))
+        } else {
+            input.expected(format!("`{}`", tag.escape_debug()))
+        }
+    }
+}
+
+/// A single character matching `pred`, described as `what` in errors.
+pub fn char_if<'a>(pred: impl Fn(char) -> bool, what: &'static str) -> impl Parser<'a, char> {
+    move |input: Input<'a>| match input.rest().chars().next() {
+        Some(c) if pred(c) => Ok((c, input.advance(c.len_utf8()))),
+        _ => input.expected(what),
+    }
+}
+
+/// The longest prefix of characters matching `pred`, possibly empty.
+pub fn take_while<'a>(pred: impl Fn(char) -> bool) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        Ok((&rest[..len], input.advance(len)))
+    }
+}
+
+/// Like `take_while`, but fails with "expected `what`" if nothing matches.
+pub fn take_while1<'a>(
+    pred: impl Fn(char) -> bool,
+    what: &'static str,
+) -> impl Parser<'a, &'a str> {
+    let inner = take_while(pred);
+    move |input: Input<'a>| match inner.parse(input)? {
+        ("", _) => input.expected(what),
+        ok => Ok(ok),
+    }
+}
+
+/// Spaces and tabs, but not line breaks.
+pub fn spaces<'a>() -> impl Parser<'a, &'a str> {
+    take_while(|c| c == ' ' || c == '\t')
+}
+
+/// Any whitespace, including line breaks.
+pub fn whitespace<'a>() -> impl Parser<'a, &'a str> {
+    take_while(char::is_whitespace)
+}
+
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c| c.is_ascii_alphabetic(), "a word")
+}
+
+/// A decimal number, with a leading `-` if `T` accepts one.
+pub fn integer<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return input.expected("a number");
+        }
+        let text = &rest[..sign + digits];
+        match text.parse() {
+            Ok(n) => Ok((n, input.advance(text.len()))),
+            // `T` is unsigned.
+            Err(_) if sign == 1 && "-0".parse::<T>().is_err() => input.expected("a number"),
+            Err(_) => input.fail(format!("number `{}` is out of range", text)),
+        }
+    }
+}
+
+pub fn eof<'a>() -> impl Parser<'a, ()> {
+    move |input: Input<'a>| {
+        if input.rest().is_empty() {
+            Ok(((), input))
+        } else {
+            input.expected("end of input")
+        }
+    }
+}
+
+/// Zero or more `p`, stopping at the first failure or when `p` stops
+/// consuming input.
+pub fn many0<'a, T>(p: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |mut input: Input<'a>| {
+        let mut values = Vec::new();
+        while let Ok((value, rest)) = p.parse(input) {
+            if rest.offset() == input.offset() {
+                break;
+            }
+            values.push(value);
+            input = rest;
+        }
+        Ok((values, input))
+    }
+}
+
+pub fn many1<'a, T>(p: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    let inner = many0(p);
+    move |input: Input<'a>| {
+        let (values, rest) = inner.parse(input)?;
+        if values.is_empty() {
+            // Let the failure recorded by `p` explain what was expected.
+            return Err(Failure { _private: () });
+        }
+        Ok((values, rest))
+    }
+}
+
+/// Exactly `n` of `p`.
+pub fn times<'a, T>(n: usize, p: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |mut input: Input<'a>| {
+        let mut values = Vec::with_capacity(n);
+        for _ in 0..n {
+            let (value, rest) = p.parse(input)?;
+            values.push(value);
+            input = rest;
+        }
+        Ok((values, input))
+    }
+}
+
+/// Zero or more `p` separated by `sep`.
+pub fn separated0<'a, T, S>(
+    p: impl Parser<'a, T>,
+    sep: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut input) = match p.parse(input) {
+            Ok(ok) => ok,
+            Err(_) => return Ok((Vec::new(), input)),
+        };
+        let mut values = vec![first];
+        loop {
+            let after_sep = match sep.parse(input) {
+                Ok((_, rest)) => rest,
+                Err(_) => return Ok((values, input)),
+            };
+            match p.parse(after_sep) {
+                Ok((value, rest)) => {
+                    values.push(value);
+                    input = rest;
+                }
+                Err(_) => return Ok((values, input)),
+            }
+        }
+    }
+}
+
+/// One or more `p` separated by `sep`.
+pub fn separated1<'a, T, S>(
+    p: impl Parser<'a, T>,
+    sep: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    let inner = separated0(p, sep);
+    move |input: Input<'a>| {
+        let (values, rest) = inner.parse(input)?;
+        if values.is_empty() {
+            return Err(Failure { _private: () });
+        }
+        Ok((values, rest))
+    }
+}
+
+/// `p` between `open` and `close`.
+pub fn delimited<'a, O, T, C>(
+    open: impl Parser<'a, O>,
+    p: impl Parser<'a, T>,
+    close: impl Parser<'a, C>,
+) -> impl Parser<'a, T> {
+    move |input| {
+        let (_, rest) = open.parse(input)?;
+        let (value, rest) = p.parse(rest)?;
+        let (_, rest) = close.parse(rest)?;
+        Ok((value, rest))
+    }
+}
+
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Runs `p` on all of `src`, failing if anything is left over.
+pub fn parse_all<'a, T>(src: &'a str, p: impl Parser<'a, T>) -> Result<T, ParseError> {
+    // Parsers may run `parse_all` themselves, so keep the caller's progress.
+    let outer = FURTHEST.with(|f| f.take());
+    let result = p.skip(eof()).parse(Input { src, pos: 0 });
+    let furthest = FURTHEST.with(|f| f.replace(outer));
+    match result {
+        Ok((value, _)) => Ok(value),
+        Err(_) => {
+            let (line, column) = line_column(src, furthest.offset);
+            Err(ParseError {
+                line,
+                column,
+                message: furthest.describe(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err<T: fmt::Debug>(r: Result<T, ParseError>) -> (usize, usize, String) {
+        let e = r.unwrap_err();
+        (e.line, e.column, e.message)
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(parse_all("abc", |i| tag("abc").parse(i)), Ok("abc"));
+        assert_eq!(parse_all("-42", |i| integer::<i32>().parse(i)), Ok(-42));
+        assert_eq!(
+            err(parse_all("-42", |i| integer::<u32>().parse(i))),
+            (1, 1, "expected a number".to_string())
+        );
+        assert_eq!(
+            err(parse_all("300", |i| integer::<u8>().parse(i))),
+            (1, 1, "number `300` is out of range".to_string())
+        );
+        assert_eq!(
+            err(parse_all("abc!", |i| word().parse(i))),
+            (1, 4, "expected end of input".to_string())
+        );
+        assert_eq!(
+            parse_all("a1", |i| char_if(|c| c.is_alphabetic(), "a letter")
+                .then(integer::<u8>())
+                .parse(i)),
+            Ok(('a', 1))
+        );
+    }
+
+    #[test]
+    fn test_combinators() {
+        let list = |i| {
+            delimited(
+                tag("["),
+                separated0(integer::<i32>(), tag(",").skip(spaces())),
+                tag("]"),
+            )
+            .parse(i)
+        };
+        assert_eq!(parse_all("[1, 2,3]", list), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_all("[]", list), Ok(vec![]));
+        assert_eq!(
+            err(parse_all("[1, x]", list)),
+            (1, 5, "expected a number".to_string())
+        );
+        assert_eq!(
+            err(parse_all("[1 2]", list)),
+            (1, 3, "expected `,` or `]`".to_string())
+        );
+
+        let lines = |i| {
+            separated1(
+                many1(char_if(|c| c == '#' || c == '.', "a cell")),
+                tag("\n"),
+            )
+            .parse(i)
+        };
+        assert_eq!(
+            err(parse_all("#.\n.x", lines)),
+            (2, 2, "expected a cell, `\\n` or end of input".to_string())
+        );
+
+        let digits = |i| times(3, char_if(|c| c.is_ascii_digit(), "a digit")).parse(i);
+        assert_eq!(parse_all("123", digits), Ok(vec!['1', '2', '3']));
+        assert_eq!(
+            err(parse_all("12", digits)),
+            (1, 3, "expected a digit".to_string())
+        );
+
+        let sign = |i| tag("+").or(tag("-")).optional().spanned().parse(i);
+        assert_eq!(
+            parse_all("-", sign),
+            Ok((Some("-"), Span { start: 0, end: 1 }))
+        );
+        assert_eq!(parse_all("", sign), Ok((None, Span { start: 0, end: 0 })));
+    }
+
+    #[test]
+    fn test_password_format() {
+        // The `aoc_2020_02` line format, as a combinator.
+        let record = |i| {
+            integer::<usize>()
+                .skip(tag("-"))
+                .then(integer::<usize>())
+                .skip(tag(" "))
+                .then(char_if(|c| c.is_ascii_lowercase(), "a letter"))
+                .skip(tag(": "))
+                .then(word())
+                .map(|(((min, max), letter), password)| (min, max, letter, password))
+                .parse(i)
+        };
+        assert_eq!(parse_all("1-3 a: abcde", record), Ok((1, 3, 'a', "abcde")));
+        assert_eq!(
+            err(parse_all("1-3 a abcde", record)),
+            (1, 6, "expected `: `".to_string())
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Packet {
+        Number(u32),
+        List(Vec<Packet>),
+    }
+
+    fn packet(input: Input) -> PResult<Packet> {
+        integer()
+            .map(Packet::Number)
+            .or(delimited(tag("["), separated0(packet, tag(",")), tag("]")).map(Packet::List))
+            .parse(input)
+    }
+
+    fn expr(input: Input) -> PResult<i64> {
+        let term = |i| {
+            integer::<i64>()
+                .or(delimited(tag("("), expr, tag(")")))
+                .skip(spaces())
+                .parse(i)
+        };
+        let op = char_if(|c| c == '+' || c == '*', "an operator").skip(spaces());
+        term.then(many0(op.then(term)))
+            .map(|(first, rest)| {
+                rest.into_iter().fold(first, |acc, (op, n)| match op {
+                    '+' => acc + n,
+                    _ => acc * n,
+                })
+            })
+            .parse(input)
+    }
+
+    #[test]
+    fn test_recursion() {
+        assert_eq!(
+            parse_all("[1,[2,[]],3]", packet),
+            Ok(Packet::List(vec![
+                Packet::Number(1),
+                Packet::List(vec![Packet::Number(2), Packet::List(vec![])]),
+                Packet::Number(3),
+            ]))
+        );
+        assert_eq!(
+            err(parse_all("[1,[2,]", packet)),
+            (1, 7, "expected a number or `[`".to_string())
+        );
+        assert_eq!(parse_all("1 + 2 * 3 + 4 * 5", expr), Ok(65));
+        assert_eq!(parse_all("2 * (3 + (4 * 5))", expr), Ok(46));
+        assert_eq!(
+            err(parse_all("2 * (3 + 4", expr)),
+            (1, 11, "expected an operator or `)`".to_string())
+        );
+    }
+}
@@ -0,0 +1,358 @@
+// Downloads a day's puzzle description and keeps it next to the solution as
+// `puzzle.md`, together with the `<pre><code>` blocks in it as candidate
+// `example_<n>.txt` files, one of which usually wants to be `example.txt`.
+
+use crate::client::Client;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    /// A tag's name and its attributes.
+    Open(&'a str, &'a str),
+    Close(&'a str),
+}
+
+/// The text and tags of `html`, skipping comments.
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, rest)| rest);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = rest[1..end].trim_end_matches('/');
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else {
+            let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open(name, attributes));
+        }
+        rest = &rest[end + 1..];
+    }
+    tokens
+}
+
+/// `text` with character references like `&lt;` and `&#39;` replaced.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let c = match entity {
+            Some("lt") => Some('<'),
+            Some("gt") => Some('>'),
+            Some("amp") => Some('&'),
+            Some("quot") => Some('"'),
+            Some("apos") => Some('\''),
+            Some("nbsp") => Some(' '),
+            Some(entity) => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+            None => None,
+        };
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                unescaped.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// The contents of every `<article>` of `page`.
+fn articles(page: &str) -> Vec<&str> {
+    let mut articles = Vec::new();
+    let mut rest = page;
+    while let Some((_, article)) = rest.split_once("<article") {
+        let Some((article, after)) = article.split_once("</article>") else {
+            break;
+        };
+        articles.push(article.split_once('>').map_or("", |(_, article)| article));
+        rest = after;
+    }
+    articles
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let (_, value) = attributes.split_once(&format!("{}=\"", name))?;
+    value.split_once('"').map(|(value, _)| value)
+}
+
+/// Ends the current block with a blank line, unless it's ended already.
+fn end_block(md: &mut String) {
+    md.truncate(md.trim_end_matches(' ').len());
+    if !md.is_empty() && !md.ends_with("\n\n") {
+        md.push_str(if md.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+}
+
+/// The puzzle description on `page`, both parts of it if it's there, as
+/// Markdown. Relative links are resolved against `base_url`.
+pub fn to_markdown(page: &str, base_url: &str) -> String {
+    let mut md = String::new();
+    let (mut in_pre, mut in_code) = (false, false);
+    let mut links = Vec::new();
+    for article in articles(page) {
+        for token in tokens(article) {
+            match token {
+                Token::Text(text) if in_pre => md.push_str(&unescape(text)),
+                Token::Text(text) => {
+                    let text = unescape(text);
+                    for (i, word) in text.split_whitespace().enumerate() {
+                        let start = i == 0 && !text.starts_with(char::is_whitespace);
+                        if !start && !md.is_empty() && !md.ends_with(['\n', ' ']) {
+                            md.push(' ');
+                        }
+                        for c in word.chars() {
+                            if !in_code && matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+                                md.push('\\');
+                            }
+                            md.push(c);
+                        }
+                    }
+                    if text.ends_with(char::is_whitespace) && !md.ends_with(['\n', ' ']) {
+                        md.push(' ');
+                    }
+                }
+                Token::Open("h2", _) => {
+                    end_block(&mut md);
+                    md.push_str("## ");
+                }
+                Token::Open("p" | "ul", _) | Token::Close("h2" | "p" | "ul") => end_block(&mut md),
+                Token::Open("li", _) => {
+                    md.truncate(md.trim_end_matches(' ').len());
+                    if !md.is_empty() && !md.ends_with('\n') {
+                        md.push('\n');
+                    }
+                    md.push_str("- ");
+                }
+                Token::Open("pre", _) => {
+                    end_block(&mut md);
+                    md.push_str("```\n");
+                    in_pre = true;
+                }
+                Token::Close("pre") => {
+                    if !md.ends_with('\n') {
+                        md.push('\n');
+                    }
+                    md.push_str("```");
+                    end_block(&mut md);
+                    in_pre = false;
+                }
+                Token::Open("code", _) if !in_pre => {
+                    md.push('`');
+                    in_code = true;
+                }
+                Token::Close("code") if !in_pre => {
+                    md.push('`');
+                    in_code = false;
+                }
+                Token::Open("em", _) | Token::Close("em") if !in_pre && !in_code => md.push('*'),
+                Token::Open("a", attributes) => {
+                    md.push('[');
+                    links.push(attribute(attributes, "href").unwrap_or_default());
+                }
+                Token::Close("a") => {
+                    let href = links.pop().unwrap_or_default();
+                    if href.starts_with('/') {
+                        md.push_str(&format!("]({}{})", base_url, href));
+                    } else {
+                        md.push_str(&format!("]({})", href));
+                    }
+                }
+                _ => {}
+            }
+        }
+        end_block(&mut md);
+    }
+    let len = md.trim_end().len();
+    md.truncate(len);
+    md.push('\n');
+    md
+}
+
+/// The text of every `<pre><code>` block on `page`, in order.
+pub fn examples(page: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut example = None;
+    for article in articles(page) {
+        let tokens = tokens(article);
+        for (i, token) in tokens.iter().enumerate() {
+            match (token, &mut example) {
+                (Token::Open("pre", _), _)
+                    if matches!(tokens.get(i + 1), Some(Token::Open("code", _))) =>
+                {
+                    example = Some(String::new())
+                }
+                (Token::Text(text), Some(example)) => example.push_str(&unescape(text)),
+                (Token::Close("pre"), Some(_)) => {
+                    let mut text = example.take().unwrap();
+                    if !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    examples.push(text);
+                }
+                _ => {}
+            }
+        }
+    }
+    examples
+}
+
+/// Downloads the puzzle of a day into `dir` as `puzzle.md`, with its
+/// examples as `example_<n>.txt`, and returns the paths it wrote.
+pub fn download(client: &Client, dir: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, String> {
+    let page = client.get(&format!("/{}/day/{}", year, day))?;
+    let markdown = to_markdown(&page, &client.base_url);
+    if markdown.trim().is_empty() {
+        return Err(format!("no puzzle for {} day {}", year, day));
+    }
+    let write = |name: String, text: &str| {
+        let path = dir.join(name);
+        fs::write(&path, text)
+            .map(|()| path.clone())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut written = vec![write("puzzle.md".to_string(), &markdown)?];
+    for (i, example) in examples(&page).iter().enumerate() {
+        written.push(write(format!("example_{}.txt", i + 1), example)?);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Day 5 - Advent of Code 2020</title></head>
+<body><main>
+<article class="day-desc"><h2>--- Day 5: Binary Boarding ---</h2><p>You board your plane
+only to discover a new problem: you dropped your <a href="/2020/day/4">boarding pass</a>!</p>
+<p>For example, consider just the first seven characters of <code>FBFBBFFRLR</code>:</p>
+<ul>
+<li>Start by considering the whole range, rows <code>0</code> through <code>127</code>.</li>
+<li>The row is <em>never</em> <code>&lt;0</code>; see <a href="https://example.com/x_y" target="_blank">this</a>.</li>
+</ul>
+<pre><code>BFFFBBFRRR: row 70, column 7, seat ID <em>567</em>
+FFFBBBFRRR: row 14, column 7, seat ID 119
+</code></pre>
+<p>As a sanity check, what is the <em>highest seat ID</em> on a boarding pass?</p>
+</article>
+<p>Your puzzle answer was <code>989</code>.</p><article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p><span title="Honest">Ding!</span> The 2*2 &amp; <code>a_b</code>.</p>
+<pre><code>FBFBBFFRLR
+</code></pre>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            to_markdown(PAGE, "https://adventofcode.com"),
+            "## --- Day 5: Binary Boarding ---
+
+You board your plane only to discover a new problem: you dropped your [boarding pass](https://adventofcode.com/2020/day/4)!
+
+For example, consider just the first seven characters of `FBFBBFFRLR`:
+
+- Start by considering the whole range, rows `0` through `127`.
+- The row is *never* `<0`; see [this](https://example.com/x_y).
+
+```
+BFFFBBFRRR: row 70, column 7, seat ID 567
+FFFBBBFRRR: row 14, column 7, seat ID 119
+```
+
+As a sanity check, what is the *highest seat ID* on a boarding pass?
+
+## --- Part Two ---
+
+Ding! The 2\\*2 & `a_b`.
+
+```
+FBFBBFFRLR
+```
+"
+        );
+        assert_eq!(to_markdown("<p>Not found</p>", ""), "\n");
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            examples(PAGE),
+            vec![
+                "BFFFBBFRRR: row 70, column 7, seat ID 567\nFFFBBBFRRR: row 14, column 7, seat ID 119\n",
+                "FBFBBFFRLR\n",
+            ]
+        );
+        assert_eq!(
+            examples("<article><pre>not code</pre></article>"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape("a &lt;b&gt; &amp;&#39;&#x41;&bogus; &"),
+            "a <b> &'A&bogus; &"
+        );
+    }
+
+    #[test]
+    fn test_download() {
+        let dir = std::env::temp_dir().join(format!("aoc_puzzle_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let (base_url, server) = client::fake_server(vec![(200, PAGE), (404, "")]);
+        let client = Client::new(&base_url, "");
+        let written = download(&client, &dir, 2020, 5).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.join("puzzle.md"),
+                dir.join("example_1.txt"),
+                dir.join("example_2.txt")
+            ]
+        );
+        let puzzle = fs::read_to_string(dir.join("puzzle.md")).unwrap();
+        assert!(puzzle.contains(&format!("]({}/2020/day/4)", base_url)));
+        assert_eq!(
+            fs::read_to_string(dir.join("example_2.txt")).unwrap(),
+            "FBFBBFFRLR\n"
+        );
+        assert!(download(&client, &dir, 2020, 26).is_err());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2020/day/5 HTTP/1.1\r\n"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,485 @@
+// Every day declared with `aoc_day!`, so tools can run them without knowing
+// each module's signatures.
+
+use crate::rng::Rng;
+use std::fmt::Display;
+
+/// Converts whatever a part returns into a printable answer or an error.
+pub trait Answer {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn into_answer(self) -> Result<String, String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer!(i32, i64, u32, u64, usize, String);
+
+impl<T: Display, E: Display> Answer for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map(|t| t.to_string()).map_err(|e| e.to_string())
+    }
+}
+
+pub type Solver = fn(&str) -> Result<String, String>;
+
+/// Another implementation of a part that should always agree with it.
+pub struct Alternative {
+    pub name: &'static str,
+    pub solve: Solver,
+}
+
+pub struct Part {
+    pub solve: Solver,
+    /// Expected answer for `Day::example`, if the puzzle gives one.
+    pub example: Option<&'static str>,
+    /// Expected answer for `Day::input`, once it's known.
+    pub answer: Option<&'static str>,
+    pub alternatives: &'static [Alternative],
+    /// A slow, obviously correct implementation that generated inputs are
+    /// checked against. It doesn't share code with the part and may panic on
+    /// malformed input.
+    pub oracle: Option<Solver>,
+}
+
+impl Part {
+    pub fn check(&self, input: &str, expected: &str) {
+        assert_eq!((self.solve)(input), Ok(expected.to_string()));
+    }
+}
+
+/// A named example input with the answers it should give, if any.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub name: &'static str,
+    pub input: &'static str,
+    pub answers: [Option<&'static str>; 2],
+}
+
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub example: &'static str,
+    pub input: &'static str,
+    pub parts: [Part; 2],
+    /// Extra examples from the module's `examples/` directory.
+    pub examples: &'static [Example],
+    /// Generates a random, well-formed input of about `size` records
+    /// (entries, lines, passports, ...) for differential and scale tests.
+    pub generate: Option<fn(&mut Rng, usize) -> String>,
+}
+
+/// The size of the input generated from `seed` in tests, from tiny to about
+/// the size of a real input.
+fn test_size(seed: u64) -> usize {
+    1 + (seed % 8 * 32) as usize
+}
+
+impl Day {
+    /// The module name, e.g. `aoc_2020_01`.
+    pub fn name(&self) -> String {
+        format!("aoc_{}_{:02}", self.year, self.day)
+    }
+
+    /// `example.txt` as an example named `example`, followed by the extra
+    /// examples.
+    pub fn all_examples(&self) -> impl Iterator<Item = Example> + '_ {
+        let main = Example {
+            name: "example",
+            input: self.example,
+            answers: [self.parts[0].example, self.parts[1].example],
+        };
+        std::iter::once(main).chain(self.examples.iter().copied())
+    }
+
+    pub fn find_example(&self, name: &str) -> Option<Example> {
+        self.all_examples().find(|e| e.name == name)
+    }
+
+    pub fn check_example(&self, example: &Example) {
+        for (part, expected) in self.parts.iter().zip(example.answers) {
+            if let Some(expected) = expected {
+                part.check(example.input, expected);
+            }
+        }
+    }
+
+    /// The input generated from `seed`, if the day has a generator.
+    pub fn generated(&self, seed: u64, size: usize) -> Option<String> {
+        self.generate
+            .map(|generate| generate(&mut Rng::new(seed), size))
+    }
+
+    /// Checks that every alternative of part `index + 1` gives the same
+    /// result as the part on the examples that apply to it, the input and
+    /// inputs generated from `seeds`.
+    pub fn check_alternatives(&self, index: usize, seeds: std::ops::Range<u64>) {
+        let part = &self.parts[index];
+        let mut inputs = self
+            .all_examples()
+            .filter(|e| e.answers[index].is_some())
+            .map(|e| (e.name.to_string(), e.input.to_string()))
+            .collect::<Vec<_>>();
+        inputs.push(("input".to_string(), self.input.to_string()));
+        for seed in seeds {
+            if let Some(input) = self.generated(seed, test_size(seed)) {
+                inputs.push((format!("seed {}", seed), input));
+            }
+        }
+        for (name, input) in &inputs {
+            let expected = (part.solve)(input);
+            for alternative in part.alternatives {
+                assert_eq!(
+                    (alternative.solve)(input),
+                    expected,
+                    "{} part {} `{}` on {}",
+                    self.name(),
+                    index + 1,
+                    alternative.name,
+                    name
+                );
+            }
+        }
+    }
+
+    /// Checks that the oracle of part `index + 1` gives the expected answers
+    /// for the examples and the input, and the same answers as the part for
+    /// inputs generated from `seeds`.
+    pub fn check_oracle(&self, index: usize, seeds: std::ops::Range<u64>) {
+        let part = &self.parts[index];
+        let oracle = part.oracle.expect("no oracle");
+        let examples = self
+            .all_examples()
+            .map(|e| (e.name, e.input, e.answers[index]));
+        let input = ("input", self.input, part.answer);
+        for (name, input, expected) in examples.chain([input]) {
+            if let Some(expected) = expected {
+                assert_eq!(
+                    oracle(input),
+                    Ok(expected.to_string()),
+                    "{} part {} oracle on {}",
+                    self.name(),
+                    index + 1,
+                    name
+                );
+            }
+        }
+        for seed in seeds {
+            let input = self.generated(seed, test_size(seed)).expect("no generator");
+            assert_eq!(
+                (part.solve)(&input),
+                oracle(&input),
+                "{} part {} on seed {}",
+                self.name(),
+                index + 1,
+                seed
+            );
+        }
+    }
+}
+
+/// Declares a day in a module with `example.txt` and `input.txt` next to it
+/// and `part_1`/`part_2` functions, or others given as `solve: some_fn,`
+/// first thing in a part: defines `EXAMPLE`, `INPUT` and the `DAY`
+/// registry entry, and generates `part_1::example`, `part_1::answer`, etc.
+/// tests for the expected answers given. Examples in `examples/` become
+/// `EXAMPLES` and an `examples::<name>` test each (see `build.rs`).
+///
+/// ```ignore
+/// aoc_day! {
+///     year: 2020,
+///     day: 1,
+///     part_1: { example: 514579, answer: 157059 },
+///     part_2: { example: 241861950, answer: 165080960 },
+/// }
+/// ```
+///
+/// With `parse: some_fn,` the parts take `&T` where `some_fn` is
+/// `fn(&str) -> Result<T, E>`, and parse errors are reported like any other.
+///
+/// With `generate: some_fn,` taking a `&mut Rng` and a size and returning an
+/// input, and `alternatives: [part_1_fast, ...]` after a part's answers, a
+/// `part_1::alternatives` test checks that the alternatives agree with the
+/// part on the examples, the input and generated inputs.
+///
+/// With `oracle: some_module,` whose `part_1` and `part_2` take the input as
+/// `&str`, `part_1::oracle` and `part_2::oracle` tests check the oracles
+/// against the expected answers, and the parts against the oracles on
+/// generated inputs.
+macro_rules! aoc_day {
+    (
+        year: $year:literal,
+        day: $day:literal,
+        $(parse: $parse:path,)?
+        $(generate: $generate:path,)?
+        $(oracle: $oracle:ident,)?
+        part_1: {
+            $(solve: $solve_1:path,)?
+            $(example: $example_1:literal,)?
+            $(answer: $answer_1:literal $(,)?)?
+            $(alternatives: $alternatives_1:tt $(,)?)?
+        },
+        part_2: {
+            $(solve: $solve_2:path,)?
+            $(example: $example_2:literal,)?
+            $(answer: $answer_2:literal $(,)?)?
+            $(alternatives: $alternatives_2:tt $(,)?)?
+        } $(,)?
+    ) => {
+        pub static EXAMPLE: &str = std::include_str!("example.txt");
+        pub static INPUT: &str = std::include_str!("input.txt");
+
+        pub static DAY: $crate::registry::Day = $crate::registry::Day {
+            year: $year,
+            day: $day,
+            example: EXAMPLE,
+            input: INPUT,
+            parts: [
+                $crate::registry::Part {
+                    solve: |input| $crate::registry::aoc_day!(
+                        @solve [part_1 $(, $solve_1)?], input $(, $parse)?
+                    ),
+                    example: $crate::registry::aoc_day!(@expected $($example_1)?),
+                    answer: $crate::registry::aoc_day!(@expected $($answer_1)?),
+                    alternatives: $crate::registry::aoc_day!(
+                        @alternatives $($alternatives_1)? $(, $parse)?
+                    ),
+                    oracle: $crate::registry::aoc_day!(@oracle part_1 $(, $oracle)?),
+                },
+                $crate::registry::Part {
+                    solve: |input| $crate::registry::aoc_day!(
+                        @solve [part_2 $(, $solve_2)?], input $(, $parse)?
+                    ),
+                    example: $crate::registry::aoc_day!(@expected $($example_2)?),
+                    answer: $crate::registry::aoc_day!(@expected $($answer_2)?),
+                    alternatives: $crate::registry::aoc_day!(
+                        @alternatives $($alternatives_2)? $(, $parse)?
+                    ),
+                    oracle: $crate::registry::aoc_day!(@oracle part_2 $(, $oracle)?),
+                },
+            ],
+            examples: EXAMPLES,
+            generate: $crate::registry::aoc_day!(@generate $($generate)?),
+        };
+
+        std::include!(std::concat!(
+            std::env!("OUT_DIR"),
+            "/examples_",
+            $year,
+            "_",
+            $day,
+            ".rs"
+        ));
+
+        #[cfg(test)]
+        mod part_1 {
+            $(
+                #[test]
+                fn example() {
+                    super::DAY.parts[0].check(super::EXAMPLE, std::concat!($example_1));
+                }
+            )?
+            $(
+                #[test]
+                fn answer() {
+                    super::DAY.parts[0].check(super::INPUT, std::concat!($answer_1));
+                }
+            )?
+            $($crate::registry::aoc_day!(@alternatives_test 0, $alternatives_1);)?
+            $($crate::registry::aoc_day!(@oracle_test 0, $oracle);)?
+        }
+
+        #[cfg(test)]
+        mod part_2 {
+            $(
+                #[test]
+                fn example() {
+                    super::DAY.parts[1].check(super::EXAMPLE, std::concat!($example_2));
+                }
+            )?
+            $(
+                #[test]
+                fn answer() {
+                    super::DAY.parts[1].check(super::INPUT, std::concat!($answer_2));
+                }
+            )?
+            $($crate::registry::aoc_day!(@alternatives_test 1, $alternatives_2);)?
+            $($crate::registry::aoc_day!(@oracle_test 1, $oracle);)?
+        }
+    };
+    (@solve [$default:ident], $($rest:tt)*) => {
+        $crate::registry::aoc_day!(@solve $default, $($rest)*)
+    };
+    (@solve [$default:ident, $part:path], $($rest:tt)*) => {
+        $crate::registry::aoc_day!(@solve $part, $($rest)*)
+    };
+    (@solve $part:path, $input:ident) => {
+        $crate::registry::Answer::into_answer($part($input))
+    };
+    (@solve $part:path, $input:ident, $parse:path) => {
+        match $parse($input) {
+            Ok(parsed) => $crate::registry::Answer::into_answer($part(&parsed)),
+            Err(e) => Err(e.to_string()),
+        }
+    };
+    (@expected) => {
+        None
+    };
+    (@expected $expected:literal) => {
+        Some(std::concat!($expected))
+    };
+    (@generate) => {
+        None
+    };
+    (@generate $generate:path) => {
+        Some($generate)
+    };
+    (@oracle $part:ident) => {
+        None
+    };
+    (@oracle $part:ident, $oracle:ident) => {
+        Some(|input| $crate::registry::Answer::into_answer($oracle::$part(input)))
+    };
+    (@alternatives $(, $parse:path)?) => {
+        &[]
+    };
+    (@alternatives [$($alternative:ident),* $(,)?]) => {
+        &[$(
+            $crate::registry::Alternative {
+                name: std::stringify!($alternative),
+                solve: |input| $crate::registry::aoc_day!(@solve $alternative, input),
+            }
+        ),*]
+    };
+    (@alternatives [$($alternative:ident),* $(,)?], $parse:path) => {
+        &[$(
+            $crate::registry::Alternative {
+                name: std::stringify!($alternative),
+                solve: |input| $crate::registry::aoc_day!(@solve $alternative, input, $parse),
+            }
+        ),*]
+    };
+    (@alternatives_test $index:literal, $alternatives:tt) => {
+        #[test]
+        fn alternatives() {
+            super::DAY.check_alternatives($index, 0..100);
+        }
+    };
+    (@oracle_test $index:literal, $oracle:ident) => {
+        #[test]
+        fn oracle() {
+            super::DAY.check_oracle($index, 0..50);
+        }
+    };
+}
+
+pub(crate) use aoc_day;
+
+/// All days, in order. A test checks that every module declaring `aoc_day!`
+/// is listed.
+pub static DAYS: &[&Day] = &[
+    &crate::aoc_2020_01::DAY,
+    &crate::aoc_2020_02::DAY,
+    &crate::aoc_2020_03::DAY,
+    &crate::aoc_2020_04::DAY,
+    &crate::aoc_2020_05::DAY,
+    &crate::aoc_2020_06::DAY,
+    &crate::aoc_2021_01::DAY,
+    &crate::aoc_2021_02::DAY,
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Day> {
+    DAYS.iter()
+        .copied()
+        .find(|d| d.year == year && d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_in_order() {
+        for (a, b) in DAYS.iter().zip(DAYS.iter().skip(1)) {
+            assert!((a.year, a.day) < (b.year, b.day));
+        }
+    }
+
+    #[test]
+    fn test_every_day_is_registered() {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut declared = std::fs::read_dir(src)
+            .unwrap()
+            .filter_map(|entry| {
+                let dir = entry.unwrap().path();
+                let module = std::fs::read_to_string(dir.join("mod.rs")).ok()?;
+                module
+                    .contains("aoc_day! {")
+                    .then(|| dir.file_name().unwrap().to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>();
+        declared.sort();
+        let registered = DAYS.iter().map(|day| day.name()).collect::<Vec<_>>();
+        assert_eq!(registered, declared, "DAYS is missing a day");
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            find(2020, 4).map(|d| d.name()),
+            Some("aoc_2020_04".to_string())
+        );
+        assert!(find(2020, 25).is_none());
+    }
+
+    #[test]
+    fn test_find_example() {
+        let day = find(2020, 5).unwrap();
+        assert_eq!(day.find_example("example").unwrap().input, day.example);
+        assert_eq!(
+            day.find_example("gap").unwrap().answers,
+            [Some("12"), Some("10")]
+        );
+        assert!(day.find_example("missing").is_none());
+    }
+
+    #[test]
+    fn test_generated() {
+        for day in DAYS {
+            assert!(
+                day.parts.iter().all(|p| p.oracle.is_some()),
+                "{}",
+                day.name()
+            );
+            let input = day.generated(7, 50).unwrap();
+            assert_eq!(day.generated(7, 50), Some(input.clone()));
+            assert_ne!(day.generated(8, 50), Some(input));
+        }
+    }
+
+    #[test]
+    fn test_into_answer() {
+        assert_eq!(42usize.into_answer(), Ok("42".to_string()));
+        assert_eq!(
+            Err::<i64, _>(crate::overflow::Overflow).into_answer(),
+            Err("arithmetic overflow".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let solve = crate::aoc_2021_02::DAY.parts[0].solve;
+        assert_eq!(
+            solve("forward 5\nsideways 2\n"),
+            Err("2:1: unknown command `sideways`".to_string())
+        );
+    }
+}
@@ -0,0 +1,304 @@
+// Results of solver runs as JSON or JUnit XML, for CI and dashboards to read
+// instead of the text output.
+
+use crate::allocations::{self, Stats};
+use crate::cache::Cache;
+use crate::matrix::{self, Outcome};
+use crate::registry::Day;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// One part run on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    /// One-based.
+    pub part: usize,
+    /// What the input was, e.g. `input`, an example's name or a user's.
+    pub input: String,
+    pub expected: Option<String>,
+    pub outcome: Outcome,
+    pub duration: Duration,
+    /// With the `count-allocations` feature.
+    pub allocations: Option<Stats>,
+}
+
+impl Record {
+    /// Runs part `index + 1` of `day` on `input`, timing it and turning a
+    /// panic into a failure, unless its answer is in `cache`.
+    pub fn solve(
+        day: &Day,
+        index: usize,
+        name: &str,
+        input: &str,
+        expected: Option<&str>,
+        cache: Option<&Cache>,
+    ) -> Self {
+        let start = Instant::now();
+        let (answer, allocations) = match cache.and_then(|cache| cache.get(day, index, input)) {
+            Some(answer) => (Ok(answer), None),
+            None => {
+                let (answer, allocations) =
+                    allocations::measure(|| matrix::solve_caught(day.parts[index].solve, input));
+                if let (Some(cache), Ok(answer)) = (cache, &answer) {
+                    cache.put(day, index, input, answer);
+                }
+                (answer, allocations)
+            }
+        };
+        Record {
+            year: day.year,
+            day: day.day,
+            part: index + 1,
+            input: name.to_string(),
+            expected: expected.map(str::to_string),
+            outcome: matrix::outcome(answer, expected),
+            duration: start.elapsed(),
+            allocations,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("aoc_{}_{:02}", self.year, self.day)
+    }
+
+    pub fn answer(&self) -> Option<&str> {
+        match &self.outcome {
+            Outcome::Correct(answer) | Outcome::Unverified(answer) => Some(answer),
+            Outcome::Wrong { answer, .. } => Some(answer),
+            Outcome::Failed(_) => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match &self.outcome {
+            Outcome::Failed(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> &'static str {
+        match self.outcome {
+            Outcome::Correct(_) => "correct",
+            Outcome::Unverified(_) => "unverified",
+            Outcome::Wrong { .. } => "wrong",
+            Outcome::Failed(_) => "failed",
+        }
+    }
+}
+
+fn json_string(s: Option<&str>) -> String {
+    let Some(s) = s else {
+        return "null".to_string();
+    };
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// An array with an object per record. `duration` is in seconds, and
+/// `allocations` is only there when they're counted.
+pub fn to_json(records: &[Record]) -> String {
+    let mut json = String::from("[");
+    for (i, r) in records.iter().enumerate() {
+        json.push_str(if i == 0 { "\n  " } else { ",\n  " });
+        write!(
+            json,
+            "{{\"year\": {}, \"day\": {}, \"part\": {}, \"input\": {}, \"answer\": {}, \
+             \"expected\": {}, \"status\": \"{}\", \"duration\": {}, \"error\": {}",
+            r.year,
+            r.day,
+            r.part,
+            json_string(Some(&r.input)),
+            json_string(r.answer()),
+            json_string(r.expected.as_deref()),
+            r.status(),
+            r.duration.as_secs_f64(),
+            json_string(r.error())
+        )
+        .unwrap();
+        if let Some(stats) = r.allocations {
+            write!(
+                json,
+                ", \"allocations\": {{\"count\": {}, \"bytes\": {}, \"peak\": {}}}",
+                stats.allocations, stats.bytes, stats.peak
+            )
+            .unwrap();
+        }
+        json.push('}');
+    }
+    json.push_str(if records.is_empty() { "]\n" } else { "\n]\n" });
+    json
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut xml = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\'' => xml.push_str("&apos;"),
+            '\n' => xml.push_str("&#10;"),
+            c => xml.push(c),
+        }
+    }
+    xml
+}
+
+/// Counts of tests, failures (wrong answers) and errors (failed parts), and
+/// the total time of `records`.
+fn totals<'a>(records: impl Iterator<Item = &'a Record>) -> String {
+    let (mut tests, mut failures, mut errors, mut time) = (0, 0, 0, Duration::ZERO);
+    for r in records {
+        tests += 1;
+        failures += usize::from(matches!(r.outcome, Outcome::Wrong { .. }));
+        errors += usize::from(matches!(r.outcome, Outcome::Failed(_)));
+        time += r.duration;
+    }
+    format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\"",
+        tests,
+        failures,
+        errors,
+        time.as_secs_f64()
+    )
+}
+
+/// A test suite per day, in the order they first appear, with a test case
+/// per part and input.
+pub fn to_junit(records: &[Record]) -> String {
+    let mut days = Vec::new();
+    for r in records {
+        if !days.contains(&r.name()) {
+            days.push(r.name());
+        }
+    }
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(xml, "<testsuites {}>", totals(records.iter())).unwrap();
+    for day in days {
+        let suite = || records.iter().filter(|r| r.name() == day);
+        writeln!(xml, "  <testsuite name=\"{}\" {}>", day, totals(suite())).unwrap();
+        for r in suite() {
+            write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"part {} ({})\" time=\"{:.6}\">",
+                day,
+                r.part,
+                xml_escape(&r.input),
+                r.duration.as_secs_f64()
+            )
+            .unwrap();
+            match &r.outcome {
+                Outcome::Correct(answer) | Outcome::Unverified(answer) => {
+                    write!(xml, "<system-out>{}</system-out>", xml_escape(answer)).unwrap()
+                }
+                Outcome::Wrong { answer, expected } => write!(
+                    xml,
+                    "<failure message=\"expected {}, got {}\"/>",
+                    xml_escape(expected),
+                    xml_escape(answer)
+                )
+                .unwrap(),
+                Outcome::Failed(e) => {
+                    write!(xml, "<error message=\"{}\"/>", xml_escape(e)).unwrap()
+                }
+            }
+            xml.push_str("</testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(part: usize, input: &str, outcome: Outcome) -> Record {
+        Record {
+            year: 2020,
+            day: 5,
+            part,
+            input: input.to_string(),
+            expected: match &outcome {
+                Outcome::Correct(expected) | Outcome::Wrong { expected, .. } => {
+                    Some(expected.clone())
+                }
+                _ => None,
+            },
+            outcome,
+            duration: Duration::from_millis(1500),
+            allocations: None,
+        }
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            record(1, "input", Outcome::Correct("989".to_string())),
+            record(
+                1,
+                "a \"b\" & <c>",
+                Outcome::Wrong {
+                    answer: "12".to_string(),
+                    expected: "11".to_string(),
+                },
+            ),
+            record(2, "bob", Outcome::Failed("no\nseat".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_solve() {
+        let day = crate::registry::find(2020, 5).unwrap();
+        let r = Record::solve(day, 1, "input", day.input, Some("548"), None);
+        assert_eq!(
+            (r.part, r.status(), r.answer()),
+            (2, "correct", Some("548"))
+        );
+        let r = Record::solve(day, 1, "empty", "", None, None);
+        assert_eq!(r.error(), Some("no free seat between two taken ones"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(to_json(&[]), "[]\n");
+        assert_eq!(
+            to_json(&records()),
+            r#"[
+  {"year": 2020, "day": 5, "part": 1, "input": "input", "answer": "989", "expected": "989", "status": "correct", "duration": 1.5, "error": null},
+  {"year": 2020, "day": 5, "part": 1, "input": "a \"b\" & <c>", "answer": "12", "expected": "11", "status": "wrong", "duration": 1.5, "error": null},
+  {"year": 2020, "day": 5, "part": 2, "input": "bob", "answer": null, "expected": null, "status": "failed", "duration": 1.5, "error": "no\nseat"}
+]
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_junit() {
+        assert_eq!(
+            to_junit(&records()),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" errors="1" time="4.500000">
+  <testsuite name="aoc_2020_05" tests="3" failures="1" errors="1" time="4.500000">
+    <testcase classname="aoc_2020_05" name="part 1 (input)" time="1.500000"><system-out>989</system-out></testcase>
+    <testcase classname="aoc_2020_05" name="part 1 (a &quot;b&quot; &amp; &lt;c&gt;)" time="1.500000"><failure message="expected 11, got 12"/></testcase>
+    <testcase classname="aoc_2020_05" name="part 2 (bob)" time="1.500000"><error message="no&#10;seat"/></testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
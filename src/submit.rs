@@ -0,0 +1,472 @@
+// Submits answers and keeps track of every attempt in
+// `submissions/<year>/<day>.txt`, one per line:
+//
+//     1700000000 part_1 too_high 1024
+//     1700000010 part_1 wait_50 1000
+//
+// so that an answer that's known to be wrong, or on the wrong side of one
+// that was too high or too low, is never sent again, and nothing is sent
+// before the site said it would take answers again.
+
+use crate::client::Client;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What the site made of an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint which way.
+    Wrong,
+    /// Submitted too soon after a wrong answer; try again after this long.
+    Wait(Duration),
+    /// The part was solved already, so the answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// The verdict in the message of the page the site answers with.
+    pub fn parse(page: &str) -> Result<Verdict, String> {
+        let message = message(page);
+        let verdict = if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::Wait(wait(&message).ok_or_else(|| format!("unexpected answer: {}", message))?)
+        } else if message.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if message.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if message.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if message.contains("Did you already complete it") {
+            Verdict::AlreadySolved
+        } else {
+            return Err(format!("unexpected answer: {}", message));
+        };
+        Ok(verdict)
+    }
+
+    /// How the verdict is recorded, with the wait in whole seconds.
+    fn key(self) -> String {
+        match self {
+            Verdict::Correct => "correct".to_string(),
+            Verdict::TooHigh => "too_high".to_string(),
+            Verdict::TooLow => "too_low".to_string(),
+            Verdict::Wrong => "wrong".to_string(),
+            Verdict::Wait(duration) => format!("wait_{}", duration.as_secs()),
+            Verdict::AlreadySolved => "already_solved".to_string(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Verdict> {
+        if let Some(seconds) = key.strip_prefix("wait_") {
+            return seconds
+                .parse()
+                .ok()
+                .map(Duration::from_secs)
+                .map(Verdict::Wait);
+        }
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::AlreadySolved,
+        ]
+        .into_iter()
+        .find(|verdict| verdict.key() == key)
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(duration) => write!(f, "too soon, wait {:?}", duration),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// The text of the page's `<article>`, without tags and with runs of
+/// whitespace collapsed, or of the whole page if it has none.
+fn message(page: &str) -> String {
+    // Starts inside the `<article ...>` tag, if there is one.
+    let (article, mut in_tag) = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or((page, false), |(article, _)| (article, true));
+    let mut text = String::new();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The `1m 3s` in `You have 1m 3s left to wait.`
+fn wait(message: &str) -> Option<Duration> {
+    let (_, rest) = message.split_once("You have ")?;
+    let (left, _) = rest.split_once(" left to wait")?;
+    let mut seconds = 0;
+    for amount in left.split_whitespace() {
+        let (n, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// One answer that was submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    /// One-based.
+    pub part: usize,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} part_{} {} {}",
+            self.time,
+            self.part,
+            self.verdict.key(),
+            self.answer
+        )
+    }
+}
+
+/// Where the attempts for a day are kept, under `root`.
+pub fn attempts_path(root: &Path, year: u16, day: u8) -> PathBuf {
+    root.join(year.to_string()).join(format!("{:02}.txt", day))
+}
+
+/// The attempts in `path`, oldest first, or none if it doesn't exist.
+pub fn read_attempts(path: &Path) -> io::Result<Vec<Attempt>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let invalid = |i: usize| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{}:{}: expected `<time> part_<n> <verdict> <answer>`",
+                path.display(),
+                i + 1
+            ),
+        )
+    };
+    let mut attempts = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields = line.splitn(4, ' ');
+        let (Some(time), Some(part), Some(verdict), Some(answer)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid(i));
+        };
+        attempts.push(Attempt {
+            time: time.parse().map_err(|_| invalid(i))?,
+            part: part
+                .strip_prefix("part_")
+                .and_then(|part| part.parse().ok())
+                .ok_or_else(|| invalid(i))?,
+            verdict: Verdict::from_key(verdict).ok_or_else(|| invalid(i))?,
+            answer: answer.to_string(),
+        });
+    }
+    Ok(attempts)
+}
+
+fn append_attempt(path: &Path, attempt: &Attempt) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", attempt)
+}
+
+/// Why `answer` shouldn't be submitted for `part` at `now` (seconds since
+/// the Unix epoch), given the earlier `attempts`, if it shouldn't.
+pub fn check(attempts: &[Attempt], part: usize, answer: &str, now: u64) -> Result<(), String> {
+    for attempt in attempts {
+        if let Verdict::Wait(wait) = attempt.verdict {
+            let until = attempt.time.saturating_add(wait.as_secs());
+            if now < until {
+                return Err(Verdict::Wait(Duration::from_secs(until - now)).to_string());
+            }
+        }
+    }
+    let attempts = attempts.iter().filter(|a| a.part == part);
+    let number = answer.parse::<i128>().ok();
+    for attempt in attempts {
+        if attempt.verdict == Verdict::Correct {
+            return Err(format!(
+                "part {} was already solved with {}",
+                part, attempt.answer
+            ));
+        }
+        if attempt.verdict.is_wrong() && attempt.answer == answer {
+            return Err(format!("{} was already {}", answer, attempt.verdict));
+        }
+        let bound = attempt.answer.parse::<i128>().ok();
+        match (attempt.verdict, number, bound) {
+            (Verdict::TooHigh, Some(n), Some(bound)) if n >= bound => {
+                return Err(format!("{} is too high, {} already was", answer, bound))
+            }
+            (Verdict::TooLow, Some(n), Some(bound)) if n <= bound => {
+                return Err(format!("{} is too low, {} already was", answer, bound))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Submits `answer` for part `part` of a day, unless the attempts recorded
+/// in `path` rule it out, and records the attempt.
+pub fn submit(
+    client: &Client,
+    path: &Path,
+    (year, day): (u16, u8),
+    part: usize,
+    answer: &str,
+) -> Result<Verdict, String> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains('\n') {
+        return Err(format!("invalid answer `{}`", answer));
+    }
+    let attempts = read_attempts(path).map_err(|e| e.to_string())?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    check(&attempts, part, answer, now)?;
+    let page = client.post(
+        &format!("/{}/day/{}/answer", year, day),
+        &[("level", &part.to_string()), ("answer", answer)],
+    )?;
+    let verdict = Verdict::parse(&page)?;
+    let attempt = Attempt {
+        time: now,
+        part,
+        verdict,
+        answer: answer.to_string(),
+    };
+    append_attempt(path, &attempt).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    fn attempt(part: usize, verdict: Verdict, answer: &str) -> Attempt {
+        Attempt {
+            time: 1_700_000_000,
+            part,
+            verdict,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            Verdict::parse(&page(
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer."
+            )),
+            Ok(Verdict::Correct)
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck, ..."
+            )),
+            Ok(Verdict::TooHigh)
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Ok(Verdict::TooLow)
+        );
+        assert_eq!(
+            Verdict::parse(&page("That's not the right answer.  If you're stuck, ...")),
+            Ok(Verdict::Wrong)
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 3s left to wait."
+            )),
+            Ok(Verdict::Wait(Duration::from_secs(63)))
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Ok(Verdict::AlreadySolved)
+        );
+        assert_eq!(
+            Verdict::parse("Puzzle inputs differ by user."),
+            Err("unexpected answer: Puzzle inputs differ by user.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let attempts = [
+            attempt(1, Verdict::TooHigh, "1000"),
+            attempt(1, Verdict::TooLow, "100"),
+            attempt(1, Verdict::Wrong, "500"),
+            attempt(1, Verdict::Wait(Duration::from_secs(60)), "600"),
+            attempt(2, Verdict::Correct, "42"),
+        ];
+        let now = 1_700_000_060;
+        assert_eq!(check(&attempts, 1, "999", now), Ok(()));
+        assert_eq!(check(&attempts, 1, "600", now), Ok(()));
+        assert_eq!(
+            check(&attempts, 1, "600", now - 1),
+            Err("too soon, wait 1s".to_string())
+        );
+        assert_eq!(
+            check(&attempts, 1, "500", now),
+            Err("500 was already wrong".to_string())
+        );
+        assert_eq!(
+            check(&attempts, 1, "1000", now),
+            Err("1000 was already wrong, too high".to_string())
+        );
+        assert_eq!(
+            check(&attempts, 1, "2000", now),
+            Err("2000 is too high, 1000 already was".to_string())
+        );
+        assert_eq!(
+            check(&attempts, 1, "-5", now),
+            Err("-5 is too low, 100 already was".to_string())
+        );
+        assert_eq!(
+            check(&attempts, 2, "43", now),
+            Err("part 2 was already solved with 42".to_string())
+        );
+        assert_eq!(check(&attempts, 1, "abc", now), Ok(()));
+    }
+
+    #[test]
+    fn test_submit() {
+        let dir = std::env::temp_dir().join(format!("aoc_submit_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = attempts_path(&dir, 2020, 5);
+        let (base_url, server) = client::fake_server(vec![
+            (
+                200,
+                "<article><p>That's not the right answer; your answer is too high.</p></article>",
+            ),
+            (200, "<article><p>That's the right answer!</p></article>"),
+        ]);
+        let client = Client::new(&base_url, "abc123");
+
+        assert_eq!(
+            submit(&client, &path, (2020, 5), 1, "1000\n"),
+            Ok(Verdict::TooHigh)
+        );
+        assert_eq!(
+            submit(&client, &path, (2020, 5), 1, "1001"),
+            Err("1001 is too high, 1000 already was".to_string())
+        );
+        assert_eq!(
+            submit(&client, &path, (2020, 5), 1, "989"),
+            Ok(Verdict::Correct)
+        );
+        assert_eq!(
+            submit(&client, &path, (2020, 5), 1, "988"),
+            Err("part 1 was already solved with 989".to_string())
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2020/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("level=1&answer=1000"));
+        assert!(requests[1].ends_with("level=1&answer=989"));
+        let attempts = read_attempts(&path).unwrap();
+        assert_eq!(
+            attempts
+                .iter()
+                .map(|a| (a.part, a.verdict, a.answer.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, Verdict::TooHigh, "1000"), (1, Verdict::Correct, "989")]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_attempts() {
+        let dir = std::env::temp_dir().join(format!("aoc_attempts_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("05.txt");
+        assert_eq!(read_attempts(&path).unwrap(), vec![]);
+        fs::write(&path, "1 part_2 wrong a b\n\n").unwrap();
+        assert_eq!(
+            read_attempts(&path).unwrap(),
+            vec![Attempt {
+                time: 1,
+                part: 2,
+                verdict: Verdict::Wrong,
+                answer: "a b".to_string()
+            }]
+        );
+        fs::write(&path, "1 part_1 wait_63 7\n").unwrap();
+        assert_eq!(
+            read_attempts(&path).unwrap()[0].verdict,
+            Verdict::Wait(Duration::from_secs(63))
+        );
+        fs::write(&path, "1 part_2 right 42\n").unwrap();
+        assert_eq!(
+            read_attempts(&path).unwrap_err().to_string(),
+            format!(
+                "{}:1: expected `<time> part_<n> <verdict> <answer>`",
+                path.display()
+            )
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
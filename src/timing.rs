@@ -0,0 +1,89 @@
+// Runs every part of a set of days concurrently and times each of them, to
+// check that the whole set fits a time budget.
+
+use crate::registry::Day;
+use crate::report::Record;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Runs both parts of every day on its input, on `threads` threads, and
+/// returns their records in the order of `days`. Panics are reported as
+/// failures, like in `matrix`. Never uses the answer cache, since it's the
+/// time to compute the answers that matters.
+pub fn run(days: &[&'static Day], threads: usize) -> Vec<Record> {
+    let jobs = days
+        .iter()
+        .flat_map(|&day| (0..day.parts.len()).map(move |part| (day, part)))
+        .collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+    let records = Mutex::new(Vec::with_capacity(jobs.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                while let Some(&(day, part)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let expected = day.parts[part].answer;
+                    let record = Record::solve(day, part, "input", day.input, expected, None);
+                    records.lock().unwrap().push(record);
+                }
+            });
+        }
+    });
+    let mut records = records.into_inner().unwrap();
+    records.sort_by_key(|r| (r.year, r.day, r.part));
+    records
+}
+
+/// The time the parts would take one after the other, which is what the
+/// budget applies to, however many threads they ran on.
+pub fn total(records: &[Record]) -> Duration {
+    records.iter().map(|r| r.duration).sum()
+}
+
+/// Parses a duration such as `1s`, `250ms` or `500us`.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (n, unit) = s.split_at(digits);
+    let n = n.parse().map_err(|_| format!("invalid duration `{}`", s))?;
+    match unit {
+        "s" => Ok(Duration::from_secs(n)),
+        "ms" => Ok(Duration::from_millis(n)),
+        "us" => Ok(Duration::from_micros(n)),
+        _ => Err(format!(
+            "invalid duration `{}`, expected e.g. `1s` or `250ms`",
+            s
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_run() {
+        let records = run(registry::DAYS, 4);
+        assert_eq!(records.len(), 2 * registry::DAYS.len());
+        for (record, (day, part)) in records
+            .iter()
+            .zip(registry::DAYS.iter().flat_map(|day| [(day, 1), (day, 2)]))
+        {
+            assert_eq!(record.name(), day.name());
+            assert_eq!(record.part, part);
+            assert_eq!(record.status(), "correct");
+        }
+        assert!(total(&records) >= records[0].duration);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("500us"), Ok(Duration::from_micros(500)));
+        assert!(parse_duration("ms").is_err());
+        assert!(parse_duration("1h").is_err());
+        assert!(parse_duration("1.5s").is_err());
+    }
+}
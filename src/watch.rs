@@ -0,0 +1,150 @@
+// Pieces of `aoc watch`, which re-solves a day whenever a file in its module
+// directory changes: polling for changes and a compact view of what the
+// answers were and how they changed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The modification time of every file under a directory.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Snapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+    pub fn take(dir: &Path) -> io::Result<Snapshot> {
+        let mut snapshot = Snapshot::default();
+        snapshot.add(dir)?;
+        Ok(snapshot)
+    }
+
+    fn add(&mut self, dir: &Path) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                self.add(&entry.path())?;
+            } else {
+                self.0.insert(entry.path(), metadata.modified()?);
+            }
+        }
+        Ok(())
+    }
+
+    /// The files that were added, removed or modified since `earlier`.
+    pub fn changed(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        let mut changed = self
+            .0
+            .iter()
+            .filter(|(path, modified)| earlier.0.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone())
+            .chain(
+                earlier
+                    .0
+                    .keys()
+                    .filter(|path| !self.0.contains_key(*path))
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+        changed.sort();
+        changed
+    }
+}
+
+/// One line per input and part from the output of `aoc examples` or
+/// `aoc run`, e.g. `gap  part 1: 12 (ok)`. Parts that come before any
+/// `<name>:` line are named after `input`.
+pub fn compact(output: &str, input: &str) -> Vec<String> {
+    let mut name = input;
+    let mut lines = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("part ") {
+            lines.push(format!("{}  {}", name, line));
+        } else if let Some(header) = line.strip_suffix(':') {
+            name = header;
+        }
+    }
+    lines
+}
+
+/// `lines`, each marked with `*` if it wasn't there in `previous`, i.e. its
+/// answer or status changed.
+pub fn mark_changes(previous: &[String], lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let mark = if previous.contains(line) { ' ' } else { '*' };
+            format!("{} {}", mark, line)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_snapshot() {
+        let dir = std::env::temp_dir().join(format!("aoc_watch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("examples")).unwrap();
+        fs::write(dir.join("mod.rs"), "").unwrap();
+        fs::write(dir.join("examples/gap.txt"), "").unwrap();
+        let before = Snapshot::take(&dir).unwrap();
+        assert_eq!(Snapshot::take(&dir).unwrap(), before);
+
+        let file = fs::File::options()
+            .write(true)
+            .open(dir.join("mod.rs"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        fs::remove_file(dir.join("examples/gap.txt")).unwrap();
+        fs::write(dir.join("input.txt"), "").unwrap();
+        let after = Snapshot::take(&dir).unwrap();
+        assert_eq!(
+            after.changed(&before),
+            vec![
+                dir.join("examples/gap.txt"),
+                dir.join("input.txt"),
+                dir.join("mod.rs")
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact() {
+        let output = "example:\npart 1: 820 (ok)\npart 2: error: no seat\ngap:\npart 1: 12 (ok)\n";
+        assert_eq!(
+            compact(output, "input"),
+            vec![
+                "example  part 1: 820 (ok)",
+                "example  part 2: error: no seat",
+                "gap  part 1: 12 (ok)",
+            ]
+        );
+        assert_eq!(
+            compact("part 1: 989 (ok)\n", "input"),
+            vec!["input  part 1: 989 (ok)"]
+        );
+    }
+
+    #[test]
+    fn test_mark_changes() {
+        let previous = vec![
+            "input  part 1: 1".to_string(),
+            "input  part 2: 2".to_string(),
+        ];
+        let lines = vec![
+            "input  part 1: 1".to_string(),
+            "input  part 2: 3".to_string(),
+        ];
+        assert_eq!(
+            mark_changes(&previous, &lines),
+            vec!["  input  part 1: 1", "* input  part 2: 3"]
+        );
+    }
+}
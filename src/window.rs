@@ -0,0 +1,224 @@
+// Sliding-window analytics for sequences such as sonar sweeps.
+
+use std::collections::VecDeque;
+
+/// Counts how often a `size`-wide window sums to more than the window before it.
+///
+/// Two adjacent windows share all but their first and last elements, so only
+/// elements `size` apart need to be compared.
+pub fn count_increases<T: PartialOrd>(values: &[T], size: usize) -> usize {
+    values
+        .iter()
+        .zip(values.iter().skip(size))
+        .filter(|(a, b)| a < b)
+        .count()
+}
+
+/// Counts how often a `size`-wide window sums to less than the window before it.
+pub fn count_decreases<T: PartialOrd>(values: &[T], size: usize) -> usize {
+    values
+        .iter()
+        .zip(values.iter().skip(size))
+        .filter(|(a, b)| a > b)
+        .count()
+}
+
+/// The most values `ChangeCounter` makes room for before any arrive.
+const INITIAL_CAPACITY: usize = 1024;
+
+/// Counts window increases and decreases one value at a time, keeping only
+/// the last `size` values in memory.
+#[derive(Debug, Clone)]
+pub struct ChangeCounter<T> {
+    size: usize,
+    buffer: VecDeque<T>,
+    increases: usize,
+    decreases: usize,
+}
+
+impl<T: PartialOrd> ChangeCounter<T> {
+    pub fn new(size: usize) -> Self {
+        ChangeCounter {
+            size,
+            // The buffer grows as values come in, so a huge `size` doesn't
+            // allocate (or fail to) up front.
+            buffer: VecDeque::with_capacity(size.min(INITIAL_CAPACITY)),
+            increases: 0,
+            decreases: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.size == 0 {
+            return;
+        }
+        if self.buffer.len() == self.size {
+            let oldest = self.buffer.pop_front().unwrap();
+            if oldest < value {
+                self.increases += 1;
+            } else if oldest > value {
+                self.decreases += 1;
+            }
+        }
+        self.buffer.push_back(value);
+    }
+
+    pub fn increases(&self) -> usize {
+        self.increases
+    }
+
+    pub fn decreases(&self) -> usize {
+        self.decreases
+    }
+}
+
+/// Streaming version of `count_increases` for any source of values.
+pub fn count_increases_iter<T: PartialOrd>(
+    values: impl IntoIterator<Item = T>,
+    size: usize,
+) -> usize {
+    let mut counter = ChangeCounter::new(size);
+    values.into_iter().for_each(|v| counter.push(v));
+    counter.increases()
+}
+
+/// Streaming version of `count_decreases` for any source of values.
+pub fn count_decreases_iter<T: PartialOrd>(
+    values: impl IntoIterator<Item = T>,
+    size: usize,
+) -> usize {
+    let mut counter = ChangeCounter::new(size);
+    values.into_iter().for_each(|v| counter.push(v));
+    counter.decreases()
+}
+
+/// Keeps the indexes of a window in a deque so that the front is always the
+/// best value according to `keep`, giving O(n) for the whole sequence.
+fn rolling_extreme<T: Copy>(values: &[T], size: usize, keep: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    assert!(size > 0, "window size must be non-zero");
+    let mut deque: VecDeque<usize> = VecDeque::with_capacity(size.min(values.len()));
+    let mut result = Vec::with_capacity(values.len().saturating_sub(size - 1));
+    for (i, value) in values.iter().enumerate() {
+        while deque.back().is_some_and(|&j| !keep(&values[j], value)) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        if i - deque[0] >= size {
+            deque.pop_front();
+        }
+        if i + 1 >= size {
+            result.push(values[deque[0]]);
+        }
+    }
+    result
+}
+
+/// Minimum of every `size`-wide window.
+///
+/// # Panics
+///
+/// Panics if `size` is 0.
+pub fn rolling_min<T: Ord + Copy>(values: &[T], size: usize) -> Vec<T> {
+    rolling_extreme(values, size, |kept, next| kept < next)
+}
+
+/// Maximum of every `size`-wide window.
+///
+/// # Panics
+///
+/// Panics if `size` is 0.
+pub fn rolling_max<T: Ord + Copy>(values: &[T], size: usize) -> Vec<T> {
+    rolling_extreme(values, size, |kept, next| kept > next)
+}
+
+/// Mean of every `size`-wide window.
+///
+/// # Panics
+///
+/// Panics if `size` is 0.
+pub fn rolling_mean(values: &[i64], size: usize) -> Vec<f64> {
+    assert!(size > 0, "window size must be non-zero");
+    let mut sum: i128 = 0;
+    let mut result = Vec::with_capacity(values.len().saturating_sub(size - 1));
+    for (i, &value) in values.iter().enumerate() {
+        sum += value as i128;
+        if i >= size {
+            sum -= values[i - size] as i128;
+        }
+        if i + 1 >= size {
+            result.push(sum as f64 / size as f64);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static DEPTHS: [i64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn test_count_increases() {
+        assert_eq!(count_increases(&DEPTHS, 1), 7);
+        assert_eq!(count_increases(&DEPTHS, 3), 5);
+        assert_eq!(count_increases(&DEPTHS, 10), 0);
+        assert_eq!(count_increases(&DEPTHS, 0), 0);
+        assert_eq!(count_increases::<i64>(&[], 1), 0);
+    }
+
+    #[test]
+    fn test_count_decreases() {
+        assert_eq!(count_decreases(&DEPTHS, 1), 2);
+        assert_eq!(count_decreases(&DEPTHS, 3), 1);
+        assert_eq!(count_decreases(&DEPTHS, 10), 0);
+    }
+
+    #[test]
+    fn test_count_iter() {
+        for size in 0..12 {
+            assert_eq!(
+                count_increases_iter(DEPTHS, size),
+                count_increases(&DEPTHS, size)
+            );
+            assert_eq!(
+                count_decreases_iter(DEPTHS, size),
+                count_decreases(&DEPTHS, size)
+            );
+        }
+    }
+
+    #[test]
+    fn test_huge_window() {
+        assert_eq!(count_increases_iter(DEPTHS, usize::MAX), 0);
+        assert_eq!(count_decreases_iter(DEPTHS, usize::MAX), 0);
+    }
+
+    #[test]
+    fn test_rolling_min() {
+        assert_eq!(rolling_min(&DEPTHS, 1), DEPTHS.to_vec());
+        assert_eq!(
+            rolling_min(&DEPTHS, 3),
+            vec![199, 200, 200, 200, 200, 207, 240, 260]
+        );
+        assert_eq!(rolling_min(&DEPTHS, 10), vec![199]);
+        assert_eq!(rolling_min(&DEPTHS, 11), vec![]);
+        assert_eq!(rolling_min(&[1, 2, 3], usize::MAX), vec![]);
+    }
+
+    #[test]
+    fn test_rolling_max() {
+        assert_eq!(
+            rolling_max(&DEPTHS, 3),
+            vec![208, 210, 210, 210, 240, 269, 269, 269]
+        );
+        assert_eq!(rolling_max(&[3, 3, 3], 2), vec![3, 3]);
+    }
+
+    #[test]
+    fn test_rolling_mean() {
+        assert_eq!(rolling_mean(&[1, 2, 3, 4], 2), vec![1.5, 2.5, 3.5]);
+        assert_eq!(rolling_mean(&[1, 2, 3, 4], 4), vec![2.5]);
+        assert_eq!(rolling_mean(&[1, 2, 3, 4], 5), vec![]);
+    }
+}
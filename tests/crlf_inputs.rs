@@ -0,0 +1,31 @@
+// Every day should give the same answers for inputs saved by editors that
+// add a byte order mark, CRLF line endings or trailing whitespace.
+
+use aoc::registry::DAYS;
+
+fn windows_style(input: &str) -> String {
+    format!("\u{feff}{}", input.replace('\n', " \r\n"))
+}
+
+#[test]
+fn all_days() {
+    for day in DAYS {
+        for (i, part) in day.parts.iter().enumerate() {
+            // Some examples don't apply to both parts, e.g. 2020 day 5 has no
+            // gap in the example seats.
+            let examples = day
+                .all_examples()
+                .filter(|e| e.answers[i].is_some())
+                .map(|e| e.input);
+            for input in examples.chain([day.input]) {
+                let mangled = windows_style(input);
+                assert_eq!(
+                    (part.solve)(&mangled),
+                    (part.solve)(input),
+                    "{}",
+                    day.name()
+                );
+            }
+        }
+    }
+}
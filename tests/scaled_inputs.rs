@@ -0,0 +1,136 @@
+// Runs every day against inputs far larger than the real ones, to make sure
+// answers either come out right or fail with `Overflow` instead of silently
+// wrapping around. Each day's generator scales its input up and the oracle
+// checks the answers; the hand-written inputs below push the numbers
+// themselves to the limits, which generated inputs never get near.
+
+use aoc::overflow::Overflow;
+use aoc::registry::DAYS;
+use aoc::*;
+
+/// Records per generated input: much more than a real input, but few enough
+/// that the oracles, which are linear except for `aoc_2020_01`'s, keep up.
+const SCALED_SIZE: usize = 20_000;
+
+/// The `aoc_2020_01` oracle tries every triple of entries.
+const SCALED_SIZE_CUBIC: usize = 400;
+
+#[test]
+fn generated() {
+    for day in DAYS {
+        let size = match day.name().as_str() {
+            "aoc_2020_01" => SCALED_SIZE_CUBIC,
+            _ => SCALED_SIZE,
+        };
+        let input = day.generated(0, size).expect("no generator");
+        for (i, part) in day.parts.iter().enumerate() {
+            let oracle = part.oracle.expect("no oracle");
+            assert_eq!(
+                (part.solve)(&input),
+                oracle(&input),
+                "{} part {} at size {}",
+                day.name(),
+                i + 1,
+                size
+            );
+        }
+    }
+}
+
+fn repeat_lines(line: &str, n: usize) -> String {
+    format!("{}\n", line).repeat(n)
+}
+
+#[test]
+fn aoc_2020_01() {
+    let big = 1i64 << 40;
+    let pair = aoc_2020_01::parse(&format!("{}\n{}\n", big, 2020 - big)).unwrap();
+    assert_eq!(
+        aoc_2020_01::part_1(&pair),
+        Err(aoc_2020_01::Error::Overflow)
+    );
+    let triple = [big, big, 2020 - 2 * big];
+    assert_eq!(
+        aoc_2020_01::part_2(&triple),
+        Err(aoc_2020_01::Error::Overflow)
+    );
+
+    // Sums to 2020 only when wrapped around.
+    let wrapping = [i64::MIN + 1010; 3];
+    assert_eq!(
+        aoc_2020_01::part_1(&wrapping),
+        Err(aoc_2020_01::Error::NotFound)
+    );
+    assert_eq!(aoc_2020_01::part_2(&[]), Err(aoc_2020_01::Error::NotFound));
+}
+
+#[test]
+fn aoc_2020_02() {
+    let input = repeat_lines("1-3 a: abcde", 50_000);
+    let records = aoc_2020_02::parse(&input).unwrap();
+    assert_eq!(aoc_2020_02::part_1(&records), 50_000);
+    assert_eq!(aoc_2020_02::part_2(&records), 50_000);
+}
+
+#[test]
+fn aoc_2020_03() {
+    let row = "#".repeat(31);
+    let input = repeat_lines(&row, 1_000);
+    assert_eq!(aoc_2020_03::part_1(&input), 1_000);
+    assert_eq!(aoc_2020_03::part_2(&input), Ok(1_000u64.pow(4) * 500));
+
+    let input = repeat_lines(&row, 12_000);
+    assert_eq!(aoc_2020_03::part_2(&input), Err(Overflow));
+}
+
+#[test]
+fn aoc_2020_04() {
+    let passport =
+        "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n";
+    let input = passport
+        .repeat(20_000)
+        .replace("hgt:183cm\n", "hgt:183cm\n\n");
+    let passports = aoc_2020_04::parse(&input).unwrap();
+    assert_eq!(aoc_2020_04::part_1(&passports), 20_000);
+    assert_eq!(aoc_2020_04::part_2(&passports), 20_000);
+}
+
+#[test]
+fn aoc_2020_05() {
+    // The plane only has 1024 seats, so the full plane is as big as it gets.
+    let input: String = (0..1024u32)
+        .filter(|&id| id != 600)
+        .map(|id| aoc_2020_05::encode_seat(id) + "\n")
+        .collect();
+    let passes = aoc_2020_05::parse(&input).unwrap();
+    assert_eq!(aoc_2020_05::part_1(&passes), 1023);
+    assert_eq!(aoc_2020_05::part_2(&passes), Ok(600));
+}
+
+#[test]
+fn aoc_2020_06() {
+    let input = "abc\nabd\n".repeat(100_000).replace("abd\n", "abd\n\n");
+    assert_eq!(aoc_2020_06::part_1(&input), 400_000);
+    assert_eq!(aoc_2020_06::part_2(&input), 200_000);
+}
+
+#[test]
+fn aoc_2021_01() {
+    let input: String = (0..1_000_000i64).map(|i| format!("{}\n", i)).collect();
+    let depths = aoc_2021_01::parse(&input).unwrap();
+    assert_eq!(aoc_2021_01::part_1(&depths), 999_999);
+    assert_eq!(aoc_2021_01::part_2(&depths), 999_997);
+}
+
+#[test]
+fn aoc_2021_02() {
+    let input = repeat_lines("forward 1000000000\ndown 1000000000", 2);
+    let program = aoc_2021_02::parse(&input).unwrap();
+    assert_eq!(aoc_2021_02::part_1(&program), Ok(4_000_000_000_000_000_000));
+
+    let input = repeat_lines("forward 1000000000", 3);
+    let program = aoc_2021_02::parse(&input).unwrap();
+    assert_eq!(aoc_2021_02::part_1(&program), Err(Overflow));
+    let program = aoc_2021_02::parse("down 50000\nforward 50000\n").unwrap();
+    assert_eq!(aoc_2021_02::part_2(&program), Err(Overflow));
+}